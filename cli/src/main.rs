@@ -5,8 +5,10 @@ use itertools::Itertools;
 use p3_field::PrimeField32;
 
 use clap::Parser;
+use hypercube_recursion_executor::RecursionPublicValues;
 use hypercube_recursion_machine::{verify_compressed, SP1Proof, SP1ProofWithPublicValues};
 use hypercube_stark::{blake3_hash, sha256_hash, MachineVerifyingKey, PublicValues, Word};
+use serde::Serialize;
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
@@ -15,52 +17,107 @@ struct Args {
     proof_dir: PathBuf,
     #[clap(short, long)]
     vk_dir: PathBuf,
+    /// Print the verified public values as JSON instead of the banner.
+    #[clap(long)]
+    json: bool,
 }
 
-fn main() {
-    let args = Args::parse();
+/// The verified public values reported by `--json`.
+#[derive(Serialize, Debug)]
+struct VerifiedPublicValues {
+    start_pc: u32,
+    next_pc: u32,
+    start_shard: u32,
+    next_shard: u32,
+    committed_value_digest: Vec<u8>,
+    exit_code: u32,
+    global_cumulative_sum: GlobalCumulativeSum,
+}
 
-    let path = args.proof_dir;
+/// The coordinates of the global cumulative sum curve point.
+#[derive(Serialize, Debug)]
+struct GlobalCumulativeSum {
+    x: [u32; 7],
+    y: [u32; 7],
+}
 
-    let mut file = File::open(path).unwrap();
+impl<F: PrimeField32> From<&RecursionPublicValues<F>> for VerifiedPublicValues {
+    fn from(public_values: &RecursionPublicValues<F>) -> Self {
+        let curve = &public_values.global_cumulative_sum.0;
+        Self {
+            start_pc: public_values.start_pc.as_canonical_u32(),
+            next_pc: public_values.next_pc.as_canonical_u32(),
+            start_shard: public_values.start_shard.as_canonical_u32(),
+            next_shard: public_values.next_shard.as_canonical_u32(),
+            committed_value_digest: public_values
+                .committed_value_digest
+                .iter()
+                .flat_map(|w| w.iter().map(|x| x.as_canonical_u32() as u8))
+                .collect(),
+            exit_code: public_values.exit_code.as_canonical_u32(),
+            global_cumulative_sum: GlobalCumulativeSum {
+                x: curve.x.0.map(|x| x.as_canonical_u32()),
+                y: curve.y.0.map(|y| y.as_canonical_u32()),
+            },
+        }
+    }
+}
 
-    let proof: SP1ProofWithPublicValues = bincode::deserialize_from(&mut file).unwrap();
+fn verify(args: &Args) -> Result<VerifiedPublicValues, String> {
+    let mut file = File::open(&args.proof_dir).map_err(|e| format!("failed to open proof: {e}"))?;
 
-    let mut file = File::open(args.vk_dir).unwrap();
-    let vk: MachineVerifyingKey<_> = bincode::deserialize_from(&mut file).unwrap();
+    let proof: SP1ProofWithPublicValues = bincode::deserialize_from(&mut file)
+        .map_err(|e| format!("failed to deserialize proof: {e}"))?;
 
-    if let SP1Proof::Compressed(inner_proof) = &proof.proof {
-        let public_values: &PublicValues<[_; 4], Word<_>, _> =
-            inner_proof.proof.public_values.as_slice().borrow();
+    let mut file = File::open(&args.vk_dir).map_err(|e| format!("failed to open vk: {e}"))?;
+    let vk: MachineVerifyingKey<_> = bincode::deserialize_from(&mut file)
+        .map_err(|e| format!("failed to deserialize vk: {e}"))?;
 
-        // Get the committed value digest bytes.
-        let committed_value_digest_bytes = public_values
-            .committed_value_digest
-            .iter()
-            .flat_map(|w| w.iter().map(|x| x.as_canonical_u32() as u8))
-            .collect_vec();
+    let SP1Proof::Compressed(inner_proof) = &proof.proof else {
+        return Err("not a compressed proof".to_string());
+    };
 
-        // Make sure the committed value digest matches the public values hash.
-        // It is computationally infeasible to find two distinct inputs, one processed with
-        // SHA256 and the other with Blake3, that yield the same hash value.
-        if committed_value_digest_bytes != sha256_hash(proof.public_values.as_byte_slice())
-            && committed_value_digest_bytes != blake3_hash(proof.public_values.as_byte_slice())
-        {
-            panic!("Committed value digest does not match public values hash");
-        }
-    } else {
-        panic!("not a compressed proof");
+    let public_values: &PublicValues<[_; 4], Word<_>, _> =
+        inner_proof.proof.public_values.as_slice().borrow();
+
+    // Get the committed value digest bytes.
+    let committed_value_digest_bytes = public_values
+        .committed_value_digest
+        .iter()
+        .flat_map(|w| w.iter().map(|x| x.as_canonical_u32() as u8))
+        .collect_vec();
+
+    // Make sure the committed value digest matches the public values hash.
+    // It is computationally infeasible to find two distinct inputs, one processed with
+    // SHA256 and the other with Blake3, that yield the same hash value.
+    if committed_value_digest_bytes != sha256_hash(proof.public_values.as_byte_slice())
+        && committed_value_digest_bytes != blake3_hash(proof.public_values.as_byte_slice())
+    {
+        return Err("Committed value digest does not match public values hash".to_string());
     }
 
-    let proof = proof.proof;
-    let proof = match proof {
-        SP1Proof::Compressed(proof) => proof,
-        _ => panic!("not a compressed proof"),
-    };
+    let public_values = verify_compressed(inner_proof, &vk)
+        .map_err(|e| format!("Failed to verify compressed proof: {e}"))?;
+
+    Ok(VerifiedPublicValues::from(&public_values))
+}
 
-    let result = verify_compressed(&proof, &vk);
+fn main() {
+    let args = Args::parse();
 
-    assert!(result.is_ok(), "Failed to verify compressed proof");
+    let public_values = match verify(&args) {
+        Ok(public_values) => public_values,
+        Err(err) if args.json => {
+            println!("{}", serde_json::json!({ "error": err }));
+            std::process::exit(1);
+        }
+        Err(err) => panic!("{}", err),
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string(&public_values).unwrap());
+        return;
+    }
 
     let mut file = File::open("message.bin").unwrap();
 
@@ -455,6 +455,60 @@ impl<T> Buffer<T, CpuBackend> {
         }
     }
 
+    /// Shortens the buffer to `len` elements, dropping the rest. Does nothing if `len` is not
+    /// smaller than the current length, and leaves the capacity unchanged.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        let tail: *mut [T] = &mut self.as_mut_slice()[len..];
+
+        // SAFETY:
+        // - `tail` is a valid subslice of the initialized elements.
+        // - As in `clear`, the length is updated before dropping so that a panicking `Drop` impl
+        //   leaks the remaining tail instead of dropping elements twice.
+        unsafe {
+            self.len = len;
+            std::ptr::drop_in_place(tail);
+        }
+    }
+
+    /// Binary searches a sorted buffer for `x`, see [slice::binary_search].
+    #[inline]
+    pub fn binary_search(&self, x: &T) -> Result<usize, usize>
+    where
+        T: Ord,
+    {
+        self.as_slice().binary_search(x)
+    }
+
+    /// Returns whether the buffer contains an element equal to `x`.
+    #[inline]
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.as_slice().contains(x)
+    }
+
+    /// Applies `f` to every element in place, keeping the allocation.
+    #[inline]
+    pub fn map_in_place(&mut self, f: impl FnMut(&mut T)) {
+        self.as_mut_slice().iter_mut().for_each(f);
+    }
+
+    /// A parallel version of [Self::map_in_place].
+    #[cfg(feature = "rayon")]
+    #[inline]
+    pub fn par_map_in_place(&mut self, f: impl Fn(&mut T) + Send + Sync)
+    where
+        T: Send,
+    {
+        use rayon::prelude::*;
+        self.as_mut_slice().par_iter_mut().for_each(f);
+    }
+
     #[inline]
     pub fn resize(&mut self, new_len: usize, value: T)
     where
@@ -672,3 +726,64 @@ impl<'de, T: Deserialize<'de>> Deserialize<'de> for Buffer<T, CpuBackend> {
         Ok(Buffer::from(vec))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn test_truncate() {
+        let mut buffer = Buffer::from(vec![1u32, 2, 3, 4, 5]);
+        let capacity = buffer.capacity();
+        buffer.truncate(10);
+        assert_eq!(buffer.as_slice(), [1, 2, 3, 4, 5]);
+        buffer.truncate(2);
+        assert_eq!(buffer.as_slice(), [1, 2]);
+        assert_eq!(buffer.capacity(), capacity);
+        buffer.truncate(0);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_truncate_drops_tail() {
+        let value = Rc::new(());
+        let mut buffer = Buffer::from(vec![value.clone(); 5]);
+        assert_eq!(Rc::strong_count(&value), 6);
+        buffer.truncate(2);
+        assert_eq!(Rc::strong_count(&value), 3);
+        buffer.clear();
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn test_map_in_place() {
+        let mut buffer = Buffer::from(vec![1i32, -2, 3, 0]);
+        let (ptr, capacity) = (buffer.as_ptr(), buffer.capacity());
+        buffer.map_in_place(|x| *x = -*x);
+        assert_eq!(buffer.as_slice(), [-1, 2, -3, 0]);
+        assert_eq!((buffer.as_ptr(), buffer.capacity()), (ptr, capacity));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_map_in_place() {
+        let mut buffer = Buffer::from((0..1000i32).collect::<Vec<_>>());
+        buffer.par_map_in_place(|x| *x = -*x);
+        assert_eq!(buffer.as_slice(), (0..1000).map(|x: i32| -x).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_binary_search_and_contains() {
+        let buffer = Buffer::from(vec![1u32, 3, 5, 7]);
+        assert_eq!(buffer.binary_search(&5), Ok(2));
+        assert_eq!(buffer.binary_search(&0), Err(0));
+        assert_eq!(buffer.binary_search(&4), Err(2));
+        assert_eq!(buffer.binary_search(&8), Err(4));
+        assert!(buffer.contains(&1));
+        assert!(buffer.contains(&7));
+        assert!(!buffer.contains(&2));
+        assert!(!Buffer::<u32>::from(vec![]).contains(&0));
+    }
+}
@@ -73,6 +73,27 @@ impl<T, A: Allocator> Slice<T, A> {
         unsafe { (Self::from_slice(left), Self::from_slice(right)) }
     }
 
+    /// Returns an iterator over all overlapping sub-slices of length `size`, see [slice::windows].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    #[inline]
+    pub fn windows(&self, size: usize) -> impl Iterator<Item = &Self> {
+        self.slice.windows(size).map(|window| unsafe { Self::from_slice(window) })
+    }
+
+    /// Returns an iterator over non-overlapping sub-slices of length `size`, omitting the
+    /// remainder, see [slice::chunks_exact].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    #[inline]
+    pub fn chunks_exact(&self, size: usize) -> impl Iterator<Item = &Self> {
+        self.slice.chunks_exact(size).map(|chunk| unsafe { Self::from_slice(chunk) })
+    }
+
     /// Copies all elements from `src` into `self`, using `copy_nonoverlapping`.
     ///
     /// The length of `src` must be the same as `self`.
@@ -122,6 +143,37 @@ impl<T, A: Allocator> Slice<T, A> {
             )
         }
     }
+
+    /// Copies the contents of the slice into a newly allocated host [Vec<T>].
+    ///
+    /// This is meant for debugging. The copy is blocking: the returned vector is only read after
+    /// the device-to-host copy has been issued and completed by `allocator`. For [CpuBackend] this
+    /// is a plain memory copy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the device-to-host copy fails.
+    #[track_caller]
+    pub fn to_host_vec(&self, allocator: &A) -> Vec<T>
+    where
+        A: DeviceMemory,
+        T: Copy,
+    {
+        let mut vec = Vec::with_capacity(self.len());
+        let layout = Layout::array::<T>(self.len()).unwrap();
+        unsafe {
+            allocator
+                .copy_nonoverlapping(
+                    self.as_ptr() as *const u8,
+                    vec.as_mut_ptr() as *mut u8,
+                    layout.size(),
+                    CopyDirection::DeviceToHost,
+                )
+                .unwrap();
+            vec.set_len(self.len());
+        }
+        vec
+    }
 }
 
 macro_rules! impl_index {
@@ -204,3 +256,57 @@ impl<T: PartialEq> PartialEq for Slice<T, CpuBackend> {
 }
 
 impl<T: Eq> Eq for Slice<T, CpuBackend> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Buffer, CpuBackend, GLOBAL_CPU_BACKEND};
+
+    use super::*;
+
+    #[test]
+    fn test_buffer_split_at_mut_disjoint() {
+        let mut buffer = Buffer::<u32, CpuBackend>::from(vec![0; 8]);
+        let (left, right): (&mut Slice<u32, CpuBackend>, &mut Slice<u32, CpuBackend>) =
+            buffer.split_at_mut(3);
+        assert_eq!((left.len(), right.len()), (3, 5));
+        assert_eq!(unsafe { left.as_ptr().add(left.len()) }, right.as_ptr());
+
+        left.iter_mut().for_each(|x| *x += 1);
+        right.iter_mut().for_each(|x| *x += 2);
+        assert_eq!(buffer.as_slice(), &[1, 1, 1, 2, 2, 2, 2, 2]);
+    }
+
+    #[test]
+    fn test_to_host_vec() {
+        let buffer = Buffer::from(vec![1u32, 2, 3, 4, 5]);
+        assert_eq!(buffer.to_host_vec(&GLOBAL_CPU_BACKEND), vec![1, 2, 3, 4, 5]);
+        assert_eq!(buffer[1..4].to_host_vec(&GLOBAL_CPU_BACKEND), vec![2, 3, 4]);
+        assert!(buffer[..0].to_host_vec(&GLOBAL_CPU_BACKEND).is_empty());
+    }
+
+    #[test]
+    fn test_windows() {
+        let buffer = Buffer::from(vec![1u32, 2, 3, 4]);
+        let pairs =
+            buffer.windows(2).map(|pair: &Slice<u32>| (pair[0], pair[1])).collect::<Vec<_>>();
+        assert_eq!(pairs, [(1, 2), (2, 3), (3, 4)]);
+        assert_eq!(buffer.windows(4).count(), 1);
+        assert_eq!(buffer.windows(5).count(), 0);
+    }
+
+    #[test]
+    fn test_chunks_exact() {
+        let buffer = Buffer::from(vec![1u32, 2, 3, 4, 5]);
+        let chunks =
+            buffer.chunks_exact(2).map(|chunk: &Slice<u32>| chunk.to_vec()).collect::<Vec<_>>();
+        assert_eq!(chunks, [vec![1, 2], vec![3, 4]]);
+        assert_eq!(buffer.chunks_exact(6).count(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_windows_zero_size() {
+        let buffer = Buffer::from(vec![1u32, 2]);
+        let _ = buffer.windows(0).count();
+    }
+}
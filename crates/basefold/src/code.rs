@@ -44,6 +44,13 @@ impl<F: TwoAdicField> FriConfig<F> {
     pub const fn proof_of_work_bits(&self) -> usize {
         self.proof_of_work_bits
     }
+
+    /// The conjectured soundness bits of the configuration, `num_queries * log_blowup +
+    /// proof_of_work_bits`.
+    #[inline]
+    pub const fn soundness_bits(&self) -> usize {
+        self.num_queries * self.log_blowup + self.proof_of_work_bits
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -2,7 +2,9 @@ use std::fmt::Debug;
 use std::marker::PhantomData;
 
 use hypercube_commit::TensorCs;
-use hypercube_merkle_tree::{my_bb_16_perm, MerkleTreeTcs, Perm, Poseidon2BabyBearConfig};
+use hypercube_merkle_tree::{
+    my_bb_16_perm, MerkleTreeTcs, Perm, Poseidon2BabyBearConfig, DIGEST_SIZE,
+};
 use p3_baby_bear::BabyBear;
 use p3_challenger::{CanObserve, DuplexChallenger, FieldChallenger, GrindingChallenger};
 use p3_field::{extension::BinomialExtensionField, ExtensionField, TwoAdicField};
@@ -44,6 +46,9 @@ pub trait BasefoldConfig:
         + Clone;
 
     fn default_challenger(_verifier: &BasefoldVerifier<Self>) -> Self::Challenger;
+
+    /// The number of base field elements in a commitment digest.
+    fn digest_size() -> usize;
 }
 
 pub trait DefaultBasefoldConfig: BasefoldConfig + Sized {
@@ -85,6 +90,10 @@ impl BasefoldConfig for Poseidon2BabyBear16BasefoldConfig {
         let default_perm = my_bb_16_perm();
         DuplexChallenger::<BabyBear, Perm, 16, 8>::new(default_perm)
     }
+
+    fn digest_size() -> usize {
+        DIGEST_SIZE
+    }
 }
 
 impl DefaultBasefoldConfig for Poseidon2BabyBear16BasefoldConfig {
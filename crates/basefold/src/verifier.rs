@@ -1,4 +1,4 @@
-use hypercube_commit::{TensorCs, TensorCsOpening};
+use hypercube_commit::{SubsetTensorCs, TensorCs, TensorCsOpening};
 use hypercube_multilinear::{Evaluations, MultilinearPcsVerifier, Point};
 use hypercube_utils::reverse_bits_len;
 use itertools::Itertools;
@@ -41,6 +41,8 @@ pub enum BaseFoldVerifierError<B: BasefoldConfig> {
     QueryFinalPolyMismatch,
     #[error("sumcheck final polynomial mismatch")]
     SumcheckFinalPolyMismatch,
+    #[error("query count mismatch: expected {0}, got {1}")]
+    QueryCountMismatch(usize, usize),
 }
 
 impl<B: BasefoldConfig> std::fmt::Debug for BaseFoldVerifierError<B> {
@@ -59,6 +61,9 @@ impl<B: BasefoldConfig> std::fmt::Debug for BaseFoldVerifierError<B> {
             BaseFoldVerifierError::SumcheckFinalPolyMismatch => {
                 write!(f, "sumcheck final polynomial mismatch")
             }
+            BaseFoldVerifierError::QueryCountMismatch(expected, actual) => {
+                write!(f, "query count mismatch: expected {expected}, got {actual}")
+            }
         }
     }
 }
@@ -84,6 +89,70 @@ pub struct BasefoldProof<B: BasefoldConfig> {
     pub pow_witness: <B::Challenger as GrindingChallenger>::Witness,
 }
 
+impl<B: BasefoldConfig> BasefoldProof<B> {
+    /// The commitments to the folded polynomials produced in the FRI commit phase.
+    pub fn fri_commitment_roots(&self) -> &[<B::Tcs as TensorCs>::Commitment] {
+        &self.fri_commitments
+    }
+
+    /// The constant value of the fully folded polynomial.
+    pub fn final_poly(&self) -> B::EF {
+        self.final_poly
+    }
+
+    /// The proof-of-work witness.
+    pub fn pow_witness(&self) -> &<B::Challenger as GrindingChallenger>::Witness {
+        &self.pow_witness
+    }
+
+    /// Checks that every query opening of the proof holds exactly `num_queries` queries.
+    fn check_num_queries(&self, num_queries: usize) -> Result<(), BaseFoldVerifierError<B>> {
+        for opening in
+            self.component_polynomials_query_openings.iter().chain(&self.query_phase_openings)
+        {
+            let count = opening.values.sizes().first().copied().unwrap_or(0);
+            if count != num_queries {
+                return Err(BaseFoldVerifierError::QueryCountMismatch(num_queries, count));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<B: BasefoldConfig> BasefoldProof<B>
+where
+    B::Tcs: SubsetTensorCs,
+{
+    /// Returns a copy of the proof containing only the FRI queries at positions `keep`.
+    ///
+    /// The verifier samples query indices one after the other, so a proof restricted to the first
+    /// `k` queries (`keep == [0, 1, .., k - 1]`) verifies under a verifier with `num_queries == k`,
+    /// see [BasefoldVerifier::with_num_queries]. Any other `keep` does not match the sampled query
+    /// indices and is rejected. Every dropped query lowers the conjectured soundness by
+    /// `log_blowup` bits, see [crate::FriConfig::soundness_bits].
+    ///
+    /// # Panics
+    /// If a position in `keep` is not smaller than the number of queries of the proof.
+    pub fn with_query_subset(&self, keep: &[usize]) -> Self {
+        Self {
+            univariate_messages: self.univariate_messages.clone(),
+            fri_commitments: self.fri_commitments.clone(),
+            component_polynomials_query_openings: self
+                .component_polynomials_query_openings
+                .iter()
+                .map(|opening| opening.select_indices(keep))
+                .collect(),
+            query_phase_openings: self
+                .query_phase_openings
+                .iter()
+                .map(|opening| opening.select_indices(keep))
+                .collect(),
+            final_poly: self.final_poly,
+            pow_witness: self.pow_witness,
+        }
+    }
+}
+
 impl<B: BasefoldConfig> MultilinearPcsVerifier for BasefoldVerifier<B> {
     type F = B::F;
     type EF = B::EF;
@@ -109,13 +178,119 @@ impl<B: BasefoldConfig> MultilinearPcsVerifier for BasefoldVerifier<B> {
 }
 
 impl<B: BasefoldConfig> BasefoldVerifier<B> {
+    /// Returns the verifier with its number of FRI queries set to `num_queries`, checking that
+    /// `proof` holds exactly that many queries.
+    ///
+    /// Used with [BasefoldProof::with_query_subset] to accept proofs stripped down to fewer
+    /// queries, at the cost of `log_blowup` bits of soundness per query dropped.
+    pub fn with_num_queries(
+        mut self,
+        num_queries: usize,
+        proof: &BasefoldProof<B>,
+    ) -> Result<Self, BaseFoldVerifierError<B>> {
+        proof.check_num_queries(num_queries)?;
+        self.fri_config.num_queries = num_queries;
+        Ok(self)
+    }
+
+    /// Verifies the evaluation claims after observing a domain-separation `label`.
+    ///
+    /// The label is observed before the batching challenge is sampled, so the prover must have
+    /// observed the same label for the proof to verify.
+    pub fn verify_trusted_evaluations_labeled(
+        &self,
+        label: &[B::F],
+        commitments: &[B::Commitment],
+        point: Point<B::EF>,
+        evaluation_claims: &[Evaluations<B::EF>],
+        proof: &BasefoldProof<B>,
+        challenger: &mut B::Challenger,
+    ) -> Result<(), BaseFoldVerifierError<B>> {
+        challenger.observe_slice(label);
+        self.verify_mle_evaluations(commitments, point, evaluation_claims, proof, challenger)
+    }
+
     fn verify_mle_evaluations(
+        &self,
+        commitments: &[B::Commitment],
+        point: Point<B::EF>,
+        evaluation_claims: &[Evaluations<B::EF>],
+        proof: &BasefoldProof<B>,
+        challenger: &mut B::Challenger,
+    ) -> Result<(), BaseFoldVerifierError<B>> {
+        let mut scratch = Vec::new();
+        self.verify_mle_evaluations_with_scratch(
+            commitments,
+            point,
+            evaluation_claims,
+            proof,
+            challenger,
+            &mut scratch,
+        )
+    }
+
+    /// Verifies the evaluation claims like [MultilinearPcsVerifier::verify_trusted_evaluations],
+    /// using `scratch` to hold the per-query batch and folded evaluations.
+    ///
+    /// The contents of `scratch` are overwritten, so the same buffer can be reused across calls to
+    /// avoid reallocating it for every proof.
+    pub fn verify_mle_evaluations_with_scratch(
+        &self,
+        commitments: &[B::Commitment],
+        point: Point<B::EF>,
+        evaluation_claims: &[Evaluations<B::EF>],
+        proof: &BasefoldProof<B>,
+        challenger: &mut B::Challenger,
+        scratch: &mut Vec<B::EF>,
+    ) -> Result<(), BaseFoldVerifierError<B>> {
+        self.verify_mle_evaluations_inner(
+            commitments,
+            point,
+            evaluation_claims,
+            proof,
+            challenger,
+            scratch,
+            None,
+        )
+    }
+
+    /// Verifies the evaluation claims like [MultilinearPcsVerifier::verify_trusted_evaluations],
+    /// but queries the positions in `query_indices` instead of sampling them from the challenger.
+    ///
+    /// This breaks soundness, since the prover can then know the queries in advance. It is only
+    /// meant for writing tests targeting specific query positions.
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn verify_trusted_evaluations_with_queries(
+        &self,
+        commitments: &[B::Commitment],
+        point: Point<B::EF>,
+        evaluation_claims: &[Evaluations<B::EF>],
+        proof: &BasefoldProof<B>,
+        challenger: &mut B::Challenger,
+        query_indices: &[usize],
+    ) -> Result<(), BaseFoldVerifierError<B>> {
+        let mut scratch = Vec::new();
+        self.verify_mle_evaluations_inner(
+            commitments,
+            point,
+            evaluation_claims,
+            proof,
+            challenger,
+            &mut scratch,
+            Some(query_indices),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn verify_mle_evaluations_inner(
         &self,
         commitments: &[B::Commitment],
         mut point: Point<B::EF>,
         evaluation_claims: &[Evaluations<B::EF>],
         proof: &BasefoldProof<B>,
         challenger: &mut B::Challenger,
+        scratch: &mut Vec<B::EF>,
+        fixed_query_indices: Option<&[usize]>,
     ) -> Result<(), BaseFoldVerifierError<B>> {
         // Sample the challenge used to batch all the different polynomials.
         let batching_challenge = challenger.sample_ext_element::<B::EF>();
@@ -188,12 +363,16 @@ impl<B: BasefoldConfig> BasefoldVerifier<B> {
 
         // Sample query indices for the FRI query IOPP part of BaseFold. This part is very similar
         // to the corresponding part in the univariate FRI verifier.
-        let query_indices = (0..self.fri_config.num_queries)
-            .map(|_| challenger.sample_bits(log_len + self.fri_config.log_blowup()))
-            .collect::<Vec<_>>();
+        let query_indices = match fixed_query_indices {
+            Some(query_indices) => query_indices.to_vec(),
+            None => self.sampled_query_indices(log_len, challenger),
+        };
+        proof.check_num_queries(query_indices.len())?;
 
         // Compute the batch evaluations from the openings of the component polynomials.
-        let mut batch_evals = vec![B::EF::zero(); query_indices.len()];
+        scratch.clear();
+        scratch.resize(query_indices.len(), B::EF::zero());
+        let batch_evals = scratch;
         let mut batch_challenge_power = B::EF::one();
         for opening in proof.component_polynomials_query_openings.iter() {
             let values = &opening.values;
@@ -238,6 +417,26 @@ impl<B: BasefoldConfig> BasefoldVerifier<B> {
         Ok(())
     }
 
+    /// Samples the FRI query indices for a codeword of length `2^(log_len + log_blowup)`.
+    ///
+    /// This is the sampling used by the verifier after the proof-of-work check, exposed so that
+    /// callers can check the prover and verifier agree on the query positions.
+    pub fn sampled_query_indices(
+        &self,
+        log_len: usize,
+        challenger: &mut impl CanSampleBits<usize>,
+    ) -> Vec<usize> {
+        (0..self.fri_config.num_queries)
+            .map(|_| challenger.sample_bits(log_len + self.fri_config.log_blowup()))
+            .collect()
+    }
+
+    /// The conjectured soundness bits of the verifier's FRI configuration, see
+    /// [crate::FriConfig::soundness_bits].
+    pub const fn soundness_bits(&self) -> usize {
+        self.fri_config.soundness_bits()
+    }
+
     /// The FRI verifier for a single query. We modify this from Plonky3 to be compatible with opening
     /// only a single vector.
     fn verify_queries(
@@ -245,13 +444,13 @@ impl<B: BasefoldConfig> BasefoldVerifier<B> {
         commitments: &[<B::Tcs as TensorCs>::Commitment],
         indices: &[usize],
         final_poly: B::EF,
-        reduced_openings: Vec<B::EF>,
+        reduced_openings: &mut [B::EF],
         query_openings: &[TensorCsOpening<B::Tcs>],
         betas: &[B::EF],
     ) -> Result<(), BaseFoldVerifierError<B>> {
         let log_max_height = commitments.len() + self.fri_config.log_blowup();
 
-        let mut folded_evals = reduced_openings;
+        let folded_evals = reduced_openings;
         let mut indices = indices.to_vec();
 
         let mut xis = indices
@@ -307,8 +506,8 @@ impl<B: BasefoldConfig> BasefoldVerifier<B> {
                 .map_err(BaseFoldVerifierError::TcsError)?;
         }
 
-        for folded_eval in folded_evals {
-            if folded_eval != final_poly {
+        for folded_eval in folded_evals.iter() {
+            if *folded_eval != final_poly {
                 return Err(BaseFoldVerifierError::QueryFinalPolyMismatch);
             }
         }
@@ -316,3 +515,201 @@ impl<B: BasefoldConfig> BasefoldVerifier<B> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use hypercube_merkle_tree::{MerkleTreeTcsError, MerkleTreeTcsProof};
+    use hypercube_multilinear::MleEval;
+    use hypercube_tensor::Tensor;
+    use p3_baby_bear::BabyBear;
+    use p3_field::extension::BinomialExtensionField;
+    use p3_symmetric::{CryptographicHasher, PseudoCompressionFunction};
+
+    use crate::Poseidon2BabyBear16BasefoldConfig;
+
+    use super::*;
+
+    #[test]
+    fn test_soundness_bits() {
+        assert_eq!(crate::FriConfig::<BabyBear>::new(1, 100, 16).soundness_bits(), 116);
+        assert_eq!(crate::FriConfig::<BabyBear>::new(2, 50, 20).soundness_bits(), 120);
+        assert_eq!(crate::FriConfig::<BabyBear>::new(3, 0, 0).soundness_bits(), 0);
+        let verifier = BasefoldVerifier::<Poseidon2BabyBear16BasefoldConfig>::new(1);
+        assert_eq!(verifier.soundness_bits(), 116);
+    }
+
+    #[test]
+    fn test_sampled_query_indices_deterministic() {
+        let verifier = BasefoldVerifier::<Poseidon2BabyBear16BasefoldConfig>::new(1);
+        let log_len = 10;
+        let seeded_challenger = || {
+            let mut challenger = verifier.challenger();
+            challenger.observe_slice(&[1, 2, 3].map(BabyBear::from_canonical_u32));
+            challenger
+        };
+
+        let indices = verifier.sampled_query_indices(log_len, &mut seeded_challenger());
+        assert_eq!(indices.len(), verifier.fri_config.num_queries);
+        assert!(indices.iter().all(|&i| i < 1 << (log_len + verifier.fri_config.log_blowup())));
+        assert_eq!(indices, verifier.sampled_query_indices(log_len, &mut seeded_challenger()));
+
+        let mut other_challenger = verifier.challenger();
+        assert_ne!(indices, verifier.sampled_query_indices(log_len, &mut other_challenger));
+    }
+
+    #[test]
+    fn test_verify_with_scratch_matches_without() {
+        type EF = BinomialExtensionField<BabyBear, 4>;
+        let mut verifier = BasefoldVerifier::<Poseidon2BabyBear16BasefoldConfig>::new(1);
+        verifier.fri_config = crate::FriConfig::new(1, 4, 0);
+
+        // A single FRI round over an all-zero codeword of length 4, so that each of the four
+        // sampled queries opens an all-zero leaf.
+        let leaf = verifier.tcs.hasher.hash_iter_slices(vec![&[BabyBear::zero(); 8][..]]);
+        let opening = || TensorCsOpening {
+            values: Tensor::from(vec![BabyBear::zero(); 4 * 8]).reshape([4, 8]),
+            proof: MerkleTreeTcsProof { paths: Tensor::from(vec![leaf; 4]).reshape([4, 1]) },
+        };
+        let proof_with_final_poly =
+            |final_poly: EF| BasefoldProof::<Poseidon2BabyBear16BasefoldConfig> {
+                univariate_messages: vec![[EF::zero(), EF::zero()]],
+                fri_commitments: vec![verifier.tcs.compressor.compress([leaf, leaf])],
+                component_polynomials_query_openings: vec![],
+                query_phase_openings: vec![opening()],
+                final_poly,
+                pow_witness: BabyBear::zero(),
+            };
+
+        // Start from a buffer with stale contents and reuse it for every proof.
+        let mut scratch = vec![EF::one(); 7];
+        for proof in [proof_with_final_poly(EF::zero()), proof_with_final_poly(EF::one())] {
+            let point = Point::from_usize(0, 1);
+            let without_scratch = verifier.verify_trusted_evaluations(
+                &[],
+                point.clone(),
+                &[],
+                &proof,
+                &mut verifier.challenger(),
+            );
+            let with_scratch = verifier.verify_mle_evaluations_with_scratch(
+                &[],
+                point,
+                &[],
+                &proof,
+                &mut verifier.challenger(),
+                &mut scratch,
+            );
+            assert_eq!(format!("{with_scratch:?}"), format!("{without_scratch:?}"));
+        }
+        assert!(verifier
+            .verify_trusted_evaluations(
+                &[],
+                Point::from_usize(0, 1),
+                &[],
+                &proof_with_final_poly(EF::zero()),
+                &mut verifier.challenger()
+            )
+            .is_ok());
+        assert!(matches!(
+            verifier.verify_mle_evaluations_with_scratch(
+                &[],
+                Point::from_usize(0, 1),
+                &[],
+                &proof_with_final_poly(EF::one()),
+                &mut verifier.challenger(),
+                &mut scratch,
+            ),
+            Err(BaseFoldVerifierError::QueryFinalPolyMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_label_changes_batching_challenge() {
+        type EF = BinomialExtensionField<BabyBear, 4>;
+        let verifier = BasefoldVerifier::<Poseidon2BabyBear16BasefoldConfig>::new(1);
+        let (label_a, label_b) = ([BabyBear::one()], [BabyBear::two()]);
+        let batching_challenge = |label: &[BabyBear]| {
+            let mut challenger = verifier.challenger();
+            challenger.observe_slice(label);
+            challenger.sample_ext_element::<EF>()
+        };
+        assert_ne!(batching_challenge(&label_a), batching_challenge(&label_b));
+
+        // A one-round proof whose first sumcheck message is consistent with the batched claim under
+        // `label_a` only, at the evaluation point zero.
+        let claims = [EF::from_canonical_u32(3), EF::from_canonical_u32(5)];
+        let batched_claim = claims[0] + claims[1] * batching_challenge(&label_a);
+        let proof = BasefoldProof::<Poseidon2BabyBear16BasefoldConfig> {
+            univariate_messages: vec![[batched_claim, EF::zero()]],
+            fri_commitments: vec![[BabyBear::zero(); 8]],
+            component_polynomials_query_openings: vec![],
+            query_phase_openings: vec![],
+            final_poly: EF::zero(),
+            pow_witness: BabyBear::zero(),
+        };
+        let evaluation_claims = [Evaluations::new(vec![MleEval::from(claims.to_vec())])];
+        let verify = |label: &[BabyBear]| {
+            verifier.verify_trusted_evaluations_labeled(
+                label,
+                &[],
+                Point::from_usize(0, 1),
+                &evaluation_claims,
+                &proof,
+                &mut verifier.challenger(),
+            )
+        };
+
+        // Under `label_a` the sumcheck check passes and verification fails later on.
+        let result_a = verify(&label_a);
+        assert!(result_a.is_err());
+        assert!(!matches!(result_a, Err(BaseFoldVerifierError::Sumcheck)));
+        assert!(matches!(verify(&label_b), Err(BaseFoldVerifierError::Sumcheck)));
+    }
+
+    #[test]
+    fn test_verify_with_fixed_queries() {
+        type EF = BinomialExtensionField<BabyBear, 4>;
+        let mut verifier = BasefoldVerifier::<Poseidon2BabyBear16BasefoldConfig>::new(1);
+        verifier.fri_config = crate::FriConfig::new(1, 1, 0);
+
+        // A single FRI round over a codeword of length 4, committed as a Merkle tree with two
+        // leaves: the all-zero pair of evaluations at position 0 and an arbitrary one at position 1.
+        let zeros = [BabyBear::zero(); 8];
+        let zeros_digest = verifier.tcs.hasher.hash_iter_slices(vec![&zeros[..]]);
+        let other_digest = verifier.tcs.hasher.hash_iter_slices(vec![&[BabyBear::one(); 8][..]]);
+        let root = verifier.tcs.compressor.compress([zeros_digest, other_digest]);
+
+        let proof = BasefoldProof::<Poseidon2BabyBear16BasefoldConfig> {
+            univariate_messages: vec![[EF::zero(), EF::zero()]],
+            fri_commitments: vec![root],
+            component_polynomials_query_openings: vec![],
+            query_phase_openings: vec![TensorCsOpening {
+                values: Tensor::from(zeros.to_vec()).reshape([1, 8]),
+                proof: MerkleTreeTcsProof {
+                    paths: Tensor::from(vec![other_digest]).reshape([1, 1]),
+                },
+            }],
+            final_poly: EF::zero(),
+            pow_witness: BabyBear::zero(),
+        };
+        let verify = |query_indices: &[usize]| {
+            verifier.verify_trusted_evaluations_with_queries(
+                &[],
+                Point::from_usize(0, 1),
+                &[],
+                &proof,
+                &mut verifier.challenger(),
+                query_indices,
+            )
+        };
+
+        // Both codeword positions folding into the first leaf are accepted.
+        assert!(verify(&[0]).is_ok());
+        assert!(verify(&[1]).is_ok());
+        // Positions folding into the second leaf do not match the opened path.
+        assert!(matches!(
+            verify(&[2]),
+            Err(BaseFoldVerifierError::TcsError(MerkleTreeTcsError::RootMismatch))
+        ));
+    }
+}
@@ -43,9 +43,25 @@ pub trait TensorCs: 'static + Clone + Send + Sync {
     ) -> Result<(), Self::VerifierError>;
 }
 
+/// A tensor commitment scheme whose batch opening proofs can be restricted to a subset of the
+/// opened indices.
+pub trait SubsetTensorCs: TensorCs {
+    /// Restrict a batch opening proof to the indices at positions `keep` of the opened indices.
+    fn select_proof_indices(proof: &Self::Proof, keep: &[usize]) -> Self::Proof;
+}
+
 impl<C: TensorCs> TensorCsOpening<C> {
     #[inline]
     pub const fn new(values: Tensor<C::Data>, proof: <C as TensorCs>::Proof) -> Self {
         Self { values, proof }
     }
 }
+
+impl<C: SubsetTensorCs> TensorCsOpening<C> {
+    /// Restrict the opening to the indices at positions `keep` of the opened indices.
+    ///
+    /// The result verifies against the correspondingly restricted list of indices.
+    pub fn select_indices(&self, keep: &[usize]) -> Self {
+        Self::new(self.values.select_axis0(keep), C::select_proof_indices(&self.proof, keep))
+    }
+}
@@ -147,6 +147,20 @@ pub fn machine_air_derive(input: TokenStream) -> TokenStream {
                 }
             });
 
+            let has_public_constraints_arms = variants.iter().map(|(variant_name, field)| {
+                let field_ty = &field.ty;
+                quote! {
+                    #name::#variant_name(x) => <#field_ty as hypercube_stark::air::MachineAir<F>>::has_public_constraints(x)
+                }
+            });
+
+            let eval_public_values_arms = variants.iter().map(|(variant_name, field)| {
+                let field_ty = &field.ty;
+                quote! {
+                    #name::#variant_name(x) => <#field_ty as hypercube_stark::air::MachineAir<F>>::eval_public_values(x, builder)
+                }
+            });
+
             let machine_air = quote! {
                 impl #impl_generics hypercube_stark::air::MachineAir<F> for #name #ty_generics #where_clause {
 
@@ -162,6 +176,18 @@ pub fn machine_air_derive(input: TokenStream) -> TokenStream {
                         }
                     }
 
+                    fn has_public_constraints(&self) -> bool {
+                        match self {
+                            #(#has_public_constraints_arms,)*
+                        }
+                    }
+
+                    fn eval_public_values<AB: hypercube_stark::air::MachineAirBuilder<F = F>>(&self, builder: &mut AB) {
+                        match self {
+                            #(#eval_public_values_arms,)*
+                        }
+                    }
+
 
                 }
             };
@@ -28,6 +28,10 @@ where
     type Challenger = BC::Challenger;
     type BatchPcsVerifier = BasefoldVerifier<BC>;
     type JaggedEvaluator = E;
+
+    fn digest_size() -> usize {
+        BC::digest_size()
+    }
 }
 
 impl<BC, E> JaggedPcsVerifier<JaggedBasefoldConfig<BC, E>>
@@ -41,4 +45,22 @@ where
         let stacked_pcs_verifier = StackedPcsVerifier::new(basefold_verifer, log_stacking_height);
         Self { stacked_pcs_verifier, max_log_row_count, jagged_evaluator: E::default() }
     }
+
+    /// The log of the FRI blowup factor of the underlying basefold verifier.
+    pub fn log_blowup(&self) -> usize {
+        self.stacked_pcs_verifier.pcs_verifier.fri_config.log_blowup()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parameter_getters() {
+        let verifier = JaggedPcsVerifier::<BabyBearPoseidon2>::new(1, 20, 21);
+        assert_eq!(verifier.log_blowup(), 1);
+        assert_eq!(verifier.log_stacking_height(), 20);
+        assert_eq!(verifier.max_log_row_count(), 21);
+    }
 }
@@ -40,4 +40,7 @@ pub trait JaggedConfig: 'static + Clone + Send + Clone + Serialize + Deserialize
         + Sync
         + Serialize
         + DeserializeOwned;
+
+    /// The number of base field elements in a commitment digest.
+    fn digest_size() -> usize;
 }
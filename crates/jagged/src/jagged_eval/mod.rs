@@ -1,6 +1,10 @@
 mod sumcheck_eval;
+#[cfg(any(test, feature = "test-util"))]
+mod trivial_eval;
 
 pub use sumcheck_eval::*;
+#[cfg(any(test, feature = "test-util"))]
+pub use trivial_eval::*;
 
 use std::{error::Error, fmt::Debug};
 
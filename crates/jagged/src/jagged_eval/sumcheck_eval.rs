@@ -48,8 +48,7 @@ where
     ) -> Result<EF, Self::JaggedEvalError> {
         let JaggedSumcheckEvalProof { branching_program_evals, partial_sumcheck_proof } = proof;
         // Calculate the partial lagrange from z_col point.
-        let z_col_partial_lagrange = Mle::blocking_partial_lagrange(z_col);
-        let z_col_partial_lagrange = z_col_partial_lagrange.guts().as_slice();
+        let z_col_partial_lagrange = z_col.eq_weights();
 
         // Calcuate the jagged eval from the branching program eval claims.
         let jagged_eval = z_col_partial_lagrange
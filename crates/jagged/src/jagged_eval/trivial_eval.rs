@@ -0,0 +1,80 @@
+use std::{convert::Infallible, marker::PhantomData};
+
+use hypercube_multilinear::Point;
+use p3_field::{ExtensionField, Field};
+use serde::{Deserialize, Serialize};
+
+use crate::JaggedLittlePolynomialVerifierParams;
+
+use super::JaggedEvalConfig;
+
+/// A jagged evaluator that computes the jagged little polynomial directly, without a proof.
+///
+/// This is only meant for testing the surrounding verifier logic against a ground-truth evaluator,
+/// as the direct evaluation is too expensive for the recursive verifier.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TrivialJaggedEvalConfig<F>(PhantomData<F>);
+
+impl<F, EF, Challenger> JaggedEvalConfig<F, EF, Challenger> for TrivialJaggedEvalConfig<F>
+where
+    F: Field,
+    EF: ExtensionField<F>,
+{
+    type JaggedEvalProof = ();
+    type JaggedEvalError = Infallible;
+
+    fn jagged_evaluation(
+        &self,
+        params: &JaggedLittlePolynomialVerifierParams<F>,
+        z_row: &Point<EF>,
+        z_col: &Point<EF>,
+        z_trace: &Point<EF>,
+        _proof: &Self::JaggedEvalProof,
+        _challenger: &mut Challenger,
+    ) -> Result<EF, Self::JaggedEvalError> {
+        Ok(params.full_jagged_little_polynomial_evaluation(z_row, z_col, z_trace).0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+
+    use super::*;
+
+    #[test]
+    fn test_trivial_eval_is_jagged_indicator() {
+        // Three columns of heights 2, 3 and 1, laid out one after the other in a trace of length 8.
+        let prefix_sums = [0, 2, 5, 6];
+        let params = JaggedLittlePolynomialVerifierParams {
+            col_prefix_sums: prefix_sums.iter().map(|&t| Point::from_usize(t, 4)).collect(),
+            max_log_row_count: 2,
+        };
+        let config = TrivialJaggedEvalConfig::<BabyBear>::default();
+
+        for col in 0..4 {
+            for row in 0..4 {
+                for index in 0..8 {
+                    let eval = JaggedEvalConfig::<BabyBear, BabyBear, ()>::jagged_evaluation(
+                        &config,
+                        &params,
+                        &Point::from_usize(row, 2),
+                        &Point::from_usize(col, 2),
+                        &Point::from_usize(index, 3),
+                        &(),
+                        &mut (),
+                    )
+                    .unwrap();
+                    let expected =
+                        col < 3 && index == prefix_sums[col] + row && index < prefix_sums[col + 1];
+                    assert_eq!(
+                        eval,
+                        BabyBear::from_bool(expected),
+                        "col {col}, row {row}, index {index}"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -25,9 +25,10 @@ use std::array;
 
 use rayon::prelude::*;
 
-use p3_field::{AbstractExtensionField, AbstractField};
+use p3_field::{AbstractExtensionField, AbstractField, Field};
 use rayon::iter::ParallelIterator;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use hypercube_multilinear::{Mle, Point};
 
@@ -173,6 +174,50 @@ pub struct JaggedLittlePolynomialVerifierParams<K> {
     pub max_log_row_count: usize,
 }
 
+/// An error returned when the column prefix sums of [JaggedLittlePolynomialVerifierParams] are
+/// malformed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum JaggedParamsError {
+    #[error("no column prefix sums")]
+    Empty,
+    #[error("column prefix sum {0} is not a point on the Boolean hypercube")]
+    NotBoolean(usize),
+    #[error("column prefix sum {0} is smaller than the previous one")]
+    NotMonotonic(usize),
+    #[error("column prefix sum {0} has a different dimension than the first one")]
+    DimensionMismatch(usize),
+}
+
+impl<F: Field> JaggedLittlePolynomialVerifierParams<F> {
+    /// Checks that the column prefix sums are Boolean points of the same dimension whose values,
+    /// read as big-endian integers, are non-decreasing.
+    pub fn validate(&self) -> Result<(), JaggedParamsError> {
+        let Some(first) = self.col_prefix_sums.first() else {
+            return Err(JaggedParamsError::Empty);
+        };
+        let dimension = first.dimension();
+        for (i, prefix_sum) in self.col_prefix_sums.iter().enumerate() {
+            if prefix_sum.dimension() != dimension {
+                return Err(JaggedParamsError::DimensionMismatch(i));
+            }
+            if prefix_sum.iter().any(|&bit| bit != F::zero() && bit != F::one()) {
+                return Err(JaggedParamsError::NotBoolean(i));
+            }
+            // For Boolean points of equal dimension, the lexicographic order of the big-endian
+            // bits is the integer order.
+            if i > 0 && bits(prefix_sum).lt(bits(&self.col_prefix_sums[i - 1])) {
+                return Err(JaggedParamsError::NotMonotonic(i));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The bits of a Boolean point, most significant first.
+fn bits<F: Field>(point: &Point<F>) -> impl Iterator<Item = bool> + '_ {
+    point.iter().map(|&bit| bit == F::one())
+}
+
 impl<F: AbstractField + 'static + Send + Sync> JaggedLittlePolynomialVerifierParams<F> {
     /// Given `z_index`, evaluate the special multilinear polynomial appearing in the jagged sumcheck
     /// protocol.
@@ -184,8 +229,7 @@ impl<F: AbstractField + 'static + Send + Sync> JaggedLittlePolynomialVerifierPar
         z_col: &Point<EF>,
         z_index: &Point<EF>,
     ) -> (EF, Vec<EF>) {
-        let z_col_partial_lagrange = Mle::blocking_partial_lagrange(z_col);
-        let z_col_partial_lagrange = z_col_partial_lagrange.guts().as_slice();
+        let z_col_partial_lagrange = z_col.eq_weights();
 
         // The program below reads only the first log_m +1 bits of z_row, but z_row could in theory
         // be longer than that if the total trace area is less than the padded height. This
@@ -211,30 +255,36 @@ impl<F: AbstractField + 'static + Send + Sync> JaggedLittlePolynomialVerifierPar
             branching_program_evals.set_len(self.col_prefix_sums.len() - 1);
         }
         let next_col_prefix_sums = self.col_prefix_sums.iter().skip(1);
-        let res = self
+        let columns = self
             .col_prefix_sums
             .iter()
             .zip(next_col_prefix_sums)
             .zip(branching_program_evals.iter_mut())
-            .enumerate()
-            .par_bridge()
-            .map(|(col_num, ((prefix_sum, next_prefix_sum), branching_program_eval))| {
-                // For `z_col` on the Boolean hypercube, this is the delta function to pick out
-                // the right column count for the current table.
-                let c_tab_correction = z_col_partial_lagrange[col_num].clone();
-
-                let prefix_sum_ef =
-                    prefix_sum.iter().map(|x| EF::from(x.clone())).collect::<Point<EF>>();
-                let next_prefix_sum_ef =
-                    next_prefix_sum.iter().map(|x| EF::from(x.clone())).collect::<Point<EF>>();
-                *branching_program_eval =
-                    branching_program.eval(&prefix_sum_ef, &next_prefix_sum_ef);
-
-                // Perform the multiplication outside of the main loop to avoid redundant
-                // multiplications.
-                z_row_correction.clone() * c_tab_correction.clone() * branching_program_eval.clone()
-            })
-            .sum::<EF>();
+            .enumerate();
+        let eval_column = |(col_num, ((prefix_sum, next_prefix_sum), branching_program_eval)): (
+            usize,
+            ((&Point<F>, &Point<F>), &mut EF),
+        )| {
+            // For `z_col` on the Boolean hypercube, this is the delta function to pick out
+            // the right column count for the current table.
+            let c_tab_correction = z_col_partial_lagrange[col_num].clone();
+
+            let prefix_sum_ef =
+                prefix_sum.iter().map(|x| EF::from(x.clone())).collect::<Point<EF>>();
+            let next_prefix_sum_ef =
+                next_prefix_sum.iter().map(|x| EF::from(x.clone())).collect::<Point<EF>>();
+            *branching_program_eval = branching_program.eval(&prefix_sum_ef, &next_prefix_sum_ef);
+
+            // Perform the multiplication outside of the main loop to avoid redundant
+            // multiplications.
+            z_row_correction.clone() * c_tab_correction.clone() * branching_program_eval.clone()
+        };
+        // The sum is exact in the field, so both paths give identical results.
+        let res = if cfg!(feature = "single-thread") {
+            columns.map(eval_column).sum::<EF>()
+        } else {
+            columns.par_bridge().map(eval_column).sum::<EF>()
+        };
 
         (res, branching_program_evals)
     }
@@ -322,11 +372,85 @@ impl<K: AbstractField + 'static> BranchingProgram<K> {
 
     /// We assume that the point is in big-endian order.
     fn get_ith_least_significant_val(point: &Point<K>, i: usize) -> K {
-        let dim = point.dimension();
-        if dim <= i {
-            K::zero()
-        } else {
-            point.get(dim - i - 1).expect("index out of bounds").clone()
+        point
+            .dimension()
+            .checked_sub(i + 1)
+            .and_then(|index| point.coord(index))
+            .cloned()
+            .unwrap_or_else(K::zero)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+
+    use super::*;
+
+    fn params(prefix_sums: &[&[u32]]) -> JaggedLittlePolynomialVerifierParams<BabyBear> {
+        JaggedLittlePolynomialVerifierParams {
+            col_prefix_sums: prefix_sums
+                .iter()
+                .map(|bits| bits.iter().map(|&bit| BabyBear::from_canonical_u32(bit)).collect())
+                .collect(),
+            max_log_row_count: 4,
         }
     }
+
+    #[test]
+    fn test_ith_least_significant_val_out_of_range() {
+        let point = [1, 2, 3].map(BabyBear::from_canonical_u32).into_iter().collect::<Point<_>>();
+        let val = |i| BranchingProgram::get_ith_least_significant_val(&point, i);
+        assert_eq!(val(0), BabyBear::from_canonical_u32(3));
+        assert_eq!(val(2), BabyBear::one());
+        assert_eq!(val(3), BabyBear::zero());
+    }
+
+    #[test]
+    fn test_validate_monotone() {
+        assert_eq!(params(&[&[0, 0, 0], &[0, 1, 1], &[0, 1, 1], &[1, 0, 0]]).validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_empty() {
+        assert_eq!(params(&[]).validate(), Err(JaggedParamsError::Empty));
+    }
+
+    #[test]
+    fn test_validate_non_boolean() {
+        assert_eq!(
+            params(&[&[0, 0, 0], &[0, 2, 0]]).validate(),
+            Err(JaggedParamsError::NotBoolean(1))
+        );
+    }
+
+    #[test]
+    fn test_validate_non_monotone() {
+        assert_eq!(
+            params(&[&[0, 0, 1], &[1, 0, 0], &[0, 1, 1]]).validate(),
+            Err(JaggedParamsError::NotMonotonic(2))
+        );
+    }
+
+    #[test]
+    fn test_validate_wide_prefix_sums() {
+        // More than 64 bits, which do not fit in a `usize`.
+        let mut low = vec![0; 80];
+        low[79] = 1;
+        let mut high = vec![0; 80];
+        high[0] = 1;
+        assert_eq!(params(&[low.as_slice(), high.as_slice()]).validate(), Ok(()));
+        assert_eq!(
+            params(&[high.as_slice(), low.as_slice()]).validate(),
+            Err(JaggedParamsError::NotMonotonic(1))
+        );
+    }
+
+    #[test]
+    fn test_validate_dimension_mismatch() {
+        assert_eq!(
+            params(&[&[0, 0, 1], &[0, 1]]).validate(),
+            Err(JaggedParamsError::DimensionMismatch(1))
+        );
+    }
 }
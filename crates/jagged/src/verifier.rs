@@ -1,4 +1,4 @@
-use hypercube_multilinear::{full_geq, Evaluations, Mle, Point};
+use hypercube_multilinear::{Evaluations, Mle, Point};
 use hypercube_stacked::{StackedPcsProof, StackedPcsVerifier};
 use hypercube_sumcheck::{partially_verify_sumcheck_proof, PartialSumcheckProof, SumcheckError};
 use p3_challenger::FieldChallenger;
@@ -7,7 +7,9 @@ use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use thiserror::Error;
 
-use crate::{JaggedConfig, JaggedEvalConfig, JaggedLittlePolynomialVerifierParams};
+use crate::{
+    JaggedConfig, JaggedEvalConfig, JaggedLittlePolynomialVerifierParams, JaggedParamsError,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JaggedPcsProof<C: JaggedConfig> {
@@ -39,6 +41,27 @@ pub enum JaggedPcsVerifierError<EF> {
     BooleanityCheckFailed,
     #[error("montonicity check failed")]
     MonotonicityCheckFailed,
+    #[error("commitment round count mismatch: {0} commitments, {1} column count groups")]
+    RoundCountMismatch(usize, usize),
+    #[error("no column prefix sums")]
+    EmptyColumnPrefixSums,
+    #[error("column prefix sums have different dimensions")]
+    PrefixSumDimensionMismatch,
+    #[error("no columns to verify")]
+    EmptyBatch,
+}
+
+impl<EF> From<JaggedParamsError> for JaggedPcsVerifierError<EF> {
+    fn from(error: JaggedParamsError) -> Self {
+        match error {
+            JaggedParamsError::Empty => JaggedPcsVerifierError::EmptyColumnPrefixSums,
+            JaggedParamsError::NotBoolean(_) => JaggedPcsVerifierError::BooleanityCheckFailed,
+            JaggedParamsError::NotMonotonic(_) => JaggedPcsVerifierError::MonotonicityCheckFailed,
+            JaggedParamsError::DimensionMismatch(_) => {
+                JaggedPcsVerifierError::PrefixSumDimensionMismatch
+            }
+        }
+    }
 }
 
 impl<C: JaggedConfig> JaggedPcsVerifier<C> {
@@ -46,6 +69,16 @@ impl<C: JaggedConfig> JaggedPcsVerifier<C> {
         self.stacked_pcs_verifier.challenger()
     }
 
+    /// The log of the height of the stacked columns.
+    pub fn log_stacking_height(&self) -> u32 {
+        self.stacked_pcs_verifier.log_stacking_height
+    }
+
+    /// The maximum log row count of a committed table.
+    pub fn max_log_row_count(&self) -> usize {
+        self.max_log_row_count
+    }
+
     pub fn verify_trusted_evaluations(
         &self,
         commitments: &[C::Commitment],
@@ -56,6 +89,8 @@ impl<C: JaggedConfig> JaggedPcsVerifier<C> {
         challenger: &mut C::Challenger,
     ) -> Result<(), JaggedPcsVerifierError<C::EF>> {
         let JaggedPcsProof { stacked_pcs_proof, sumcheck_proof, jagged_eval_proof, params } = proof;
+        params.validate()?;
+
         let num_col_variables = (params.col_prefix_sums.len() - 1).next_power_of_two().ilog2();
         let z_col = (0..num_col_variables)
             .map(|_| challenger.sample_ext_element::<C::EF>())
@@ -91,22 +126,6 @@ impl<C: JaggedConfig> JaggedPcsVerifier<C> {
         partially_verify_sumcheck_proof(sumcheck_proof, challenger)
             .map_err(JaggedPcsVerifierError::SumcheckError)?;
 
-        for t_col in params.col_prefix_sums.iter() {
-            for &elem in t_col.iter() {
-                if elem * (C::F::one() - elem) != C::F::zero() {
-                    return Err(JaggedPcsVerifierError::BooleanityCheckFailed);
-                }
-            }
-        }
-
-        for (t_col, next_t_col) in
-            params.col_prefix_sums.iter().zip(params.col_prefix_sums.iter().skip(1))
-        {
-            if full_geq(t_col, next_t_col) != C::F::one() {
-                return Err(JaggedPcsVerifierError::MonotonicityCheckFailed);
-            }
-        }
-
         let jagged_eval = self
             .jagged_evaluator
             .jagged_evaluation(
@@ -151,6 +170,25 @@ impl<'a, C: JaggedConfig> MachineJaggedPcsVerifier<'a, C> {
         Self { jagged_pcs_verifier, column_counts_by_round }
     }
 
+    /// The total number of columns across all rounds.
+    pub fn total_columns(&self) -> usize {
+        self.column_counts_by_round.iter().flatten().sum()
+    }
+
+    /// Checks that there is one group of column counts per commitment round.
+    pub fn validate_against(
+        &self,
+        num_commitments: usize,
+    ) -> Result<(), JaggedPcsVerifierError<C::EF>> {
+        if num_commitments != self.column_counts_by_round.len() {
+            return Err(JaggedPcsVerifierError::RoundCountMismatch(
+                num_commitments,
+                self.column_counts_by_round.len(),
+            ));
+        }
+        Ok(())
+    }
+
     pub fn verify_trusted_evaluations(
         &self,
         commitments: &[C::Commitment],
@@ -159,6 +197,12 @@ impl<'a, C: JaggedConfig> MachineJaggedPcsVerifier<'a, C> {
         proof: &JaggedPcsProof<C>,
         challenger: &mut C::Challenger,
     ) -> Result<(), JaggedPcsVerifierError<C::EF>> {
+        self.validate_against(commitments.len())?;
+        // A batch without columns has nothing committed to open, so no honest proof of it exists.
+        if self.total_columns() == 0 {
+            return Err(JaggedPcsVerifierError::EmptyBatch);
+        }
+
         let insertion_points = self
             .column_counts_by_round
             .iter()
@@ -178,3 +222,22 @@ impl<'a, C: JaggedConfig> MachineJaggedPcsVerifier<'a, C> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::BabyBearPoseidon2;
+
+    use super::*;
+
+    #[test]
+    fn test_round_count_validation() {
+        let verifier = JaggedPcsVerifier::<BabyBearPoseidon2>::new(1, 21, 21);
+        let machine_verifier = MachineJaggedPcsVerifier::new(&verifier, vec![vec![2, 3], vec![4]]);
+        assert_eq!(machine_verifier.total_columns(), 9);
+        assert!(machine_verifier.validate_against(2).is_ok());
+        assert!(matches!(
+            machine_verifier.validate_against(1),
+            Err(JaggedPcsVerifierError::RoundCountMismatch(1, 2))
+        ));
+    }
+}
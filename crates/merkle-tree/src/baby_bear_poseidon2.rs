@@ -11,9 +11,12 @@ pub struct Poseidon2BabyBearConfig<const STATE_WIDTH: usize = 16>;
 
 pub type Perm = Poseidon2<BabyBear, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
 
+/// The number of `BabyBear` elements in a Poseidon2 digest.
+pub const DIGEST_SIZE: usize = 8;
+
 impl MerkleTreeConfig for Poseidon2BabyBearConfig {
     type Data = BabyBear;
-    type Digest = [BabyBear; 8];
+    type Digest = [BabyBear; DIGEST_SIZE];
     type Hasher = PaddingFreeSponge<Perm, 16, 8, 8>;
     type Compressor = TruncatedPermutation<Perm, 2, 8, 16>;
 }
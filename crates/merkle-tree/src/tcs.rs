@@ -1,6 +1,6 @@
 use std::fmt::Debug;
 
-use hypercube_commit::{TensorCs, TensorCsOpening};
+use hypercube_commit::{SubsetTensorCs, TensorCs, TensorCsOpening};
 use hypercube_tensor::Tensor;
 use itertools::Itertools;
 use p3_symmetric::{CryptographicHasher, PseudoCompressionFunction};
@@ -41,6 +41,8 @@ pub struct MerkleTreeTcs<M: MerkleTreeConfig> {
 pub enum MerkleTreeTcsError {
     #[error("root mismatch")]
     RootMismatch,
+    #[error("{indices} indices but {values} opened values and {paths} paths")]
+    OpeningCountMismatch { indices: usize, values: usize, paths: usize },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,6 +58,25 @@ impl<M: DefaultMerkleTreeConfig> Default for MerkleTreeTcs<M> {
     }
 }
 
+impl<M: MerkleTreeConfig> MerkleTreeTcs<M> {
+    /// Checks that `opening` has one opened value and one path for each of `indices_len` indices.
+    pub fn validate_opening_against_indices(
+        opening: &TensorCsOpening<Self>,
+        indices_len: usize,
+    ) -> Result<(), MerkleTreeTcsError> {
+        let values = opening.values.sizes().first().copied().unwrap_or(0);
+        let paths = opening.proof.paths.sizes().first().copied().unwrap_or(0);
+        if values != indices_len || paths != indices_len {
+            return Err(MerkleTreeTcsError::OpeningCountMismatch {
+                indices: indices_len,
+                values,
+                paths,
+            });
+        }
+        Ok(())
+    }
+}
+
 impl<M: MerkleTreeConfig> TensorCs for MerkleTreeTcs<M> {
     type Data = M::Data;
     type Commitment = M::Digest;
@@ -68,6 +89,8 @@ impl<M: MerkleTreeConfig> TensorCs for MerkleTreeTcs<M> {
         indices: &[usize],
         opening: &TensorCsOpening<Self>,
     ) -> Result<(), Self::VerifierError> {
+        Self::validate_opening_against_indices(opening, indices.len())?;
+
         for (i, (index, path)) in indices.iter().zip_eq(opening.proof.paths.split()).enumerate() {
             // Collect the lead slices of the claimed values.
             let claimed_values_slices = opening.values.get(i).unwrap().as_slice();
@@ -93,3 +116,58 @@ impl<M: MerkleTreeConfig> TensorCs for MerkleTreeTcs<M> {
         Ok(())
     }
 }
+
+impl<M: MerkleTreeConfig> SubsetTensorCs for MerkleTreeTcs<M> {
+    fn select_proof_indices(proof: &Self::Proof, keep: &[usize]) -> Self::Proof {
+        MerkleTreeTcsProof { paths: proof.paths.select_axis0(keep) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+
+    use crate::Poseidon2BabyBearConfig;
+
+    use super::*;
+
+    type Tcs = MerkleTreeTcs<Poseidon2BabyBearConfig>;
+
+    /// The root of a two-leaf tree over rows of three elements, and an opening of both leaves.
+    fn two_leaf_opening(tcs: &Tcs) -> ([BabyBear; 8], TensorCsOpening<Tcs>) {
+        let rows = (0..6).map(BabyBear::from_canonical_u32).collect::<Vec<_>>();
+        let leaves = rows.chunks(3).map(|row| tcs.hasher.hash_slice(row)).collect::<Vec<_>>();
+        let root = tcs.compressor.compress([leaves[0], leaves[1]]);
+        let values = Tensor::from(rows).reshape([2, 3]);
+        let paths = Tensor::from(vec![leaves[1], leaves[0]]).reshape([2, 1]);
+        (root, TensorCsOpening { values, proof: MerkleTreeTcsProof { paths } })
+    }
+
+    #[test]
+    fn test_validate_opening_against_indices() {
+        let tcs = Tcs::default();
+        let (root, opening) = two_leaf_opening(&tcs);
+        assert!(Tcs::validate_opening_against_indices(&opening, 2).is_ok());
+        assert!(tcs.verify_tensor_openings(&root, &[0, 1], &opening).is_ok());
+
+        assert!(matches!(
+            Tcs::validate_opening_against_indices(&opening, 3),
+            Err(MerkleTreeTcsError::OpeningCountMismatch { indices: 3, values: 2, paths: 2 })
+        ));
+        // A mismatched count is an error instead of a panic.
+        assert!(matches!(
+            tcs.verify_tensor_openings(&root, &[0], &opening),
+            Err(MerkleTreeTcsError::OpeningCountMismatch { indices: 1, values: 2, paths: 2 })
+        ));
+
+        let opening = TensorCsOpening {
+            values: opening.values,
+            proof: Tcs::select_proof_indices(&opening.proof, &[0]),
+        };
+        assert!(matches!(
+            tcs.verify_tensor_openings(&root, &[0, 1], &opening),
+            Err(MerkleTreeTcsError::OpeningCountMismatch { indices: 2, values: 2, paths: 1 })
+        ));
+    }
+}
@@ -18,8 +18,27 @@ pub(crate) fn eval_mle_at_point_blocking<
     let dimensions = Dimensions::try_from(sizes).unwrap();
     let mut dst = Tensor { storage: buffer![], dimensions };
     let total_len = dst.total_len();
-    let dot_products = mle
-        .as_buffer()
+    let dot_products = if cfg!(feature = "single-thread") {
+        dot_products_sequential(mle, &partial_lagrange, total_len)
+    } else {
+        dot_products_parallel(mle, &partial_lagrange, total_len)
+    };
+
+    let dot_products = Buffer::from(dot_products);
+    dst.storage = dot_products;
+    dst
+}
+
+/// Sums the rows of `mle` weighted by `partial_lagrange`, in parallel.
+///
+/// Field addition is exact, so the result does not depend on the order of the reduction and
+/// matches [dot_products_sequential].
+fn dot_products_parallel<F: AbstractField + Sync, EF: AbstractExtensionField<F> + Send + Sync>(
+    mle: &Tensor<F, CpuBackend>,
+    partial_lagrange: &Tensor<EF, CpuBackend>,
+    total_len: usize,
+) -> Vec<EF> {
+    mle.as_buffer()
         .par_chunks_exact(mle.strides()[0])
         .zip(partial_lagrange.as_buffer().par_iter())
         .map(|(chunk, scalar)| chunk.iter().map(|a| scalar.clone() * a.clone()).collect())
@@ -29,9 +48,48 @@ pub(crate) fn eval_mle_at_point_blocking<
                 a.iter_mut().zip(b.iter()).for_each(|(a, b)| *a += b.clone());
                 a
             },
-        );
+        )
+}
 
-    let dot_products = Buffer::from(dot_products);
-    dst.storage = dot_products;
-    dst
+/// Sums the rows of `mle` weighted by `partial_lagrange`, in row order on the current thread.
+fn dot_products_sequential<F: AbstractField, EF: AbstractExtensionField<F>>(
+    mle: &Tensor<F, CpuBackend>,
+    partial_lagrange: &Tensor<EF, CpuBackend>,
+    total_len: usize,
+) -> Vec<EF> {
+    mle.as_buffer().chunks_exact(mle.strides()[0]).zip(partial_lagrange.as_buffer().iter()).fold(
+        vec![EF::zero(); total_len],
+        |mut acc, (chunk, scalar)| {
+            acc.iter_mut().zip(chunk.iter()).for_each(|(a, b)| *a += scalar.clone() * b.clone());
+            acc
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::extension::BinomialExtensionField;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+
+    type EF = BinomialExtensionField<BabyBear, 4>;
+
+    #[test]
+    fn test_sequential_and_parallel_dot_products_agree() {
+        let mut rng = thread_rng();
+        let num_variables = 8;
+        let width = 5;
+        let values =
+            (0..(width << num_variables)).map(|_| rng.gen::<BabyBear>()).collect::<Vec<_>>();
+        let mle = Tensor::from(values).reshape([1 << num_variables, width]);
+        let point = (0..num_variables).map(|_| rng.gen::<EF>()).collect::<Point<EF>>();
+        let partial_lagrange = partial_lagrange_blocking(&point);
+
+        assert_eq!(
+            dot_products_parallel(&mle, &partial_lagrange, width),
+            dot_products_sequential(&mle, &partial_lagrange, width)
+        );
+    }
 }
@@ -12,7 +12,10 @@ use p3_field::{AbstractExtensionField, AbstractField, Field};
 use rand::{distributions::Standard, prelude::Distribution, Rng};
 use serde::{Deserialize, Serialize};
 
-use crate::{eval::eval_mle_at_point_blocking, partial_lagrange_blocking, MleBaseBackend, Point};
+use crate::{
+    eval::eval_mle_at_point_blocking, partial_lagrange_blocking, MleBaseBackend, Point,
+    PointDimensionMismatch,
+};
 
 /// A bacth of multi-linear polynomials.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -91,6 +94,12 @@ impl<F, A: Backend> Mle<F, A> {
         &mut self.guts
     }
 
+    /// Returns the number of bytes allocated for the guts, see [Tensor::memory_bytes].
+    #[inline]
+    pub fn memory_bytes(&self) -> usize {
+        self.guts.memory_bytes()
+    }
+
     /// # Safety
     // #[inline]
     // pub unsafe fn assume_init(&mut self) {
@@ -128,6 +137,22 @@ impl<F, A: Backend> Mle<F, A> {
         A::num_non_zero_entries(&self.guts)
     }
 
+    /// Returns the `[2^log_stacking_height, num_stacked_columns]` shape that the MLE's data takes
+    /// when committed with a stacked PCS.
+    ///
+    /// The polynomials are concatenated into a single vector, which is split into columns of
+    /// `2^log_stacking_height` entries, the last one padded with zeroes.
+    #[inline]
+    pub fn stacking_shape(&self, log_stacking_height: u32) -> Vec<usize>
+    where
+        F: AbstractField,
+        A: MleBaseBackend<F>,
+    {
+        let stacking_height = 1 << log_stacking_height;
+        let total_len = self.num_polynomials() * self.num_non_zero_entries();
+        vec![stacking_height, total_len.div_ceil(stacking_height)]
+    }
+
     // /// Evaluates the MLE at a given point.
     // #[inline]
     // pub async fn eval_at<EF: AbstractExtensionField<F>>(
@@ -225,6 +250,25 @@ impl<T> Mle<T, CpuBackend> {
         Self::new(Tensor::rand(rng, [1 << num_variables, num_polynomials]))
     }
 
+    /// Checks, in debug builds only, that the guts still have the `[height, num_polynomials]`
+    /// layout of an MLE, with a power-of-two height and every entry initialized.
+    ///
+    /// This is a safety net to call after editing the tensor through [Self::guts_mut].
+    #[track_caller]
+    pub fn assert_invariants(&self) {
+        debug_assert_eq!(self.guts.sizes().len(), 2, "MLE guts must be two-dimensional");
+        debug_assert!(
+            self.guts.sizes()[0].is_power_of_two(),
+            "MLE height {} is not a power of two",
+            self.guts.sizes()[0]
+        );
+        debug_assert_eq!(
+            self.guts.as_buffer().len(),
+            self.guts.total_len(),
+            "MLE guts storage does not match its dimensions"
+        );
+    }
+
     /// Returns an iterator over the evaluations of the MLE on the Boolean hypercube.
     ///
     /// The iterator yields a slice for each index of the Boolean hypercube.
@@ -239,18 +283,89 @@ impl<T> Mle<T, CpuBackend> {
 
     /// Returns an iterator over the evaluations of the MLE on the Boolean hypercube.
     ///
-    /// The iterator yields a slice for each index of the Boolean hypercube.
+    /// The iterator yields a slice for each index of the Boolean hypercube. With the `single-thread`
+    /// feature the range is never split, so all slices are produced in order by a single job.
     pub fn hypercube_par_iter(&self) -> impl IndexedParallelIterator<Item = &[T]>
     where
         T: AbstractField + Sync,
     {
         let width = self.num_polynomials();
         let height = self.num_variables();
+        let min_len = if cfg!(feature = "single-thread") { 1 << height } else { 1 };
         (0..(1 << height))
             .into_par_iter()
+            .with_min_len(min_len)
             .map(move |i| &self.guts.as_slice()[i * width..(i + 1) * width])
     }
 
+    /// Returns the evaluations of the only polynomial of the MLE, or `None` if the MLE does not
+    /// hold exactly one polynomial.
+    ///
+    /// With a single polynomial the guts are the evaluations in hypercube order, so no copy is
+    /// needed.
+    pub fn as_single_poly(&self) -> Option<&[T]> {
+        (self.guts.sizes()[1] == 1).then(|| self.guts.as_slice())
+    }
+
+    /// Returns the evaluations laid out as `[num_polynomials, height]`, i.e. one row per
+    /// polynomial.
+    ///
+    /// The result is a plain [Tensor] rather than an [Mle], since it no longer has the
+    /// `[height, num_polynomials]` layout an MLE requires.
+    pub fn transpose(&self) -> Tensor<T, CpuBackend>
+    where
+        T: Clone,
+    {
+        self.guts.permute_axes([1, 0])
+    }
+
+    /// Combines the polynomials of the MLE into a single polynomial, weighting the `i`-th one by
+    /// `challenge^i`.
+    pub fn random_linear_combination<EF>(&self, challenge: EF) -> Mle<EF, CpuBackend>
+    where
+        T: AbstractField,
+        EF: AbstractExtensionField<T>,
+    {
+        self.hypercube_iter()
+            .map(|row| {
+                let mut power = EF::one();
+                let mut acc = EF::zero();
+                for value in row {
+                    acc += power.clone() * value.clone();
+                    power *= challenge.clone();
+                }
+                acc
+            })
+            .collect()
+    }
+
+    /// Returns whether `other` holds the same polynomials as `self` in a different order, where
+    /// polynomial `j` of `other` is polynomial `perm[j]` of `self`.
+    ///
+    /// Returns `false` if `perm` is not a permutation of `0..num_polynomials`.
+    pub fn eq_up_to_poly_permutation(&self, other: &Self, perm: &[usize]) -> bool
+    where
+        T: PartialEq,
+    {
+        let num_polynomials = self.guts.sizes()[1];
+        if self.guts.sizes() != other.guts.sizes() || perm.len() != num_polynomials {
+            return false;
+        }
+        let mut seen = vec![false; num_polynomials];
+        for &i in perm {
+            if i >= num_polynomials || std::mem::replace(&mut seen[i], true) {
+                return false;
+            }
+        }
+        if num_polynomials == 0 {
+            return true;
+        }
+        let rows = self.guts.as_slice().chunks_exact(num_polynomials);
+        let other_rows = other.guts.as_slice().chunks_exact(num_polynomials);
+        rows.zip(other_rows)
+            .all(|(row, other_row)| perm.iter().zip(other_row).all(|(&i, value)| row[i] == *value))
+    }
+
     /// # Safety
     pub unsafe fn from_raw_parts(ptr: *mut T, num_polynomials: usize, len: usize) -> Self {
         let total_len = num_polynomials * len;
@@ -284,15 +399,32 @@ impl<T> Mle<T, CpuBackend> {
     ///
     /// The polynomial f(X,Y) is an important building block in zerocheck and other protocols which use
     /// sumcheck.
+    ///
+    /// # Panics
+    /// If the dimensions of `point_1` and `point_2` do not match, see [Self::try_full_lagrange_eval]
+    /// for a non-panicking version.
     pub fn full_lagrange_eval<EF>(point_1: &Point<T>, point_2: &Point<EF>) -> EF
     where
         T: AbstractField,
         EF: AbstractExtensionField<T>,
     {
-        assert_eq!(point_1.dimension(), point_2.dimension());
+        Self::try_full_lagrange_eval(point_1, point_2).unwrap()
+    }
+
+    /// Evaluates the same polynomial as [Self::full_lagrange_eval], returning an error if the
+    /// dimensions of the points do not match.
+    pub fn try_full_lagrange_eval<EF>(
+        point_1: &Point<T>,
+        point_2: &Point<EF>,
+    ) -> Result<EF, PointDimensionMismatch>
+    where
+        T: AbstractField,
+        EF: AbstractExtensionField<T>,
+    {
+        PointDimensionMismatch::check(point_1.dimension(), point_2.dimension())?;
 
         // Iterate over all values in the n-variates X and Y.
-        point_1
+        let eval = point_1
             .iter()
             .zip(point_2.iter())
             .map(|(x, y)| {
@@ -300,7 +432,8 @@ impl<T> Mle<T, CpuBackend> {
                 let prod = y.clone() * x.clone();
                 prod.clone() + prod + EF::one() - x.clone() - y.clone()
             })
-            .product()
+            .product();
+        Ok(eval)
     }
 }
 
@@ -339,26 +472,83 @@ impl<T> FromIterator<T> for Mle<T, CpuBackend> {
 /// The multilinear polynomial whose evaluation on the Boolean hypercube performs outputs 1 if the
 /// Boolean hypercube point is the bit-string representation of a number greater than or equal to
 /// `threshold`, and 0 otherwise.
+///
+/// This materializes all `2^num_variables` evaluations. Use [partial_geq_iter] to stream them, or
+/// [full_geq] to evaluate the polynomial at a point without enumerating the hypercube at all.
 pub fn partial_geq<F: Field>(threshold: usize, num_variables: usize) -> Vec<F> {
+    partial_geq_iter(threshold, num_variables).collect()
+}
+
+/// A lazy version of [partial_geq], yielding the evaluations in the same order without allocating
+/// them.
+pub fn partial_geq_iter<F: Field>(
+    threshold: usize,
+    num_variables: usize,
+) -> impl ExactSizeIterator<Item = F> {
     assert!(threshold <= 1 << num_variables);
 
-    (0..(1 << num_variables)).map(|x| if x >= threshold { F::one() } else { F::zero() }).collect()
+    (0..(1 << num_variables)).map(move |x| if x >= threshold { F::one() } else { F::zero() })
 }
 
 /// A succinct way to compute the evaluation of `partial_geq` at `eval_point`. The threshold is passed
 /// as a `Point` on the Boolean hypercube.
 ///
 /// # Panics
-/// If the dimensions of `threshold` and `eval_point` do not match.
+/// If the dimensions of `threshold` and `eval_point` do not match, see [try_full_geq] for a
+/// non-panicking version.
 pub fn full_geq<F: AbstractField, EF: AbstractExtensionField<F>>(
     threshold: &Point<F>,
     eval_point: &Point<EF>,
 ) -> EF {
-    assert_eq!(threshold.dimension(), eval_point.dimension());
-    threshold.iter().rev().zip(eval_point.iter().rev()).fold(EF::one(), |acc, (x, y)| {
-        ((EF::one() - y.clone()) * (F::one() - x.clone()) + y.clone() * x.clone()) * acc
-            + y.clone() * (F::one() - x.clone())
-    })
+    try_full_geq(threshold, eval_point).unwrap()
+}
+
+/// Computes [full_geq], returning an error if the dimensions of `threshold` and `eval_point` do not
+/// match.
+pub fn try_full_geq<F: AbstractField, EF: AbstractExtensionField<F>>(
+    threshold: &Point<F>,
+    eval_point: &Point<EF>,
+) -> Result<EF, PointDimensionMismatch> {
+    PointDimensionMismatch::check(threshold.dimension(), eval_point.dimension())?;
+    let eval =
+        threshold.iter().rev().zip(eval_point.iter().rev()).fold(EF::one(), |acc, (x, y)| {
+            ((EF::one() - y.clone()) * (F::one() - x.clone()) + y.clone() * x.clone()) * acc
+                + y.clone() * (F::one() - x.clone())
+        });
+    Ok(eval)
+}
+
+/// Computes [full_geq] for an integer threshold, using the same ordering as [partial_geq].
+///
+/// # Panics
+/// If `threshold` is larger than `2^eval_point.dimension()`.
+pub fn full_geq_usize<EF: AbstractField>(threshold: usize, eval_point: &Point<EF>) -> EF {
+    let num_variables = eval_point.dimension();
+    assert!(threshold <= 1 << num_variables);
+    // A threshold of `2^num_variables` is not representable on the hypercube and selects nothing.
+    if threshold == 1 << num_variables {
+        return EF::zero();
+    }
+    full_geq(&Point::<EF>::from_usize(threshold, num_variables), eval_point)
+}
+
+/// Evaluates the polynomial of [Mle::full_lagrange_eval] for a pair of points which are both in
+/// the extension field.
+///
+/// # Panics
+/// If the dimensions of `point_1` and `point_2` do not match, see [try_full_lagrange_eval_ext] for
+/// a non-panicking version.
+pub fn full_lagrange_eval_ext<EF: AbstractField>(point_1: &Point<EF>, point_2: &Point<EF>) -> EF {
+    Mle::<EF>::full_lagrange_eval(point_1, point_2)
+}
+
+/// Computes [full_lagrange_eval_ext], returning an error if the dimensions of the points do not
+/// match.
+pub fn try_full_lagrange_eval_ext<EF: AbstractField>(
+    point_1: &Point<EF>,
+    point_2: &Point<EF>,
+) -> Result<EF, PointDimensionMismatch> {
+    Mle::<EF>::try_full_lagrange_eval(point_1, point_2)
 }
 
 /// A bacth of multi-linear polynomial evaluations.
@@ -433,6 +623,20 @@ impl<T> MleEval<T, CpuBackend> {
         self.evaluations.split().map(|t| t.as_slice())
     }
 
+    /// Collects an iterator of known length, allocating the evaluations buffer up front.
+    pub fn from_exact_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let iter = iter.into_iter();
+        let mut buffer = Buffer::with_capacity(iter.len());
+        for value in iter {
+            buffer.push(value);
+        }
+        Self::new(Tensor::from(buffer))
+    }
+
     pub fn add_evals(self, other: Self) -> Self
     where
         T: Add<Output = T> + Clone,
@@ -492,3 +696,219 @@ impl<T> FromIterator<T> for MleEval<T, CpuBackend> {
         Self::new(Tensor::from(iter.into_iter().collect::<Vec<_>>()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::extension::BinomialExtensionField;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+
+    type EF = BinomialExtensionField<BabyBear, 4>;
+
+    #[test]
+    fn test_transpose() {
+        let (height, num_polynomials) = (4, 3);
+        let mle = Mle::new((0..12u32).collect::<Tensor<u32>>().reshape([height, num_polynomials]));
+        let transposed = mle.transpose();
+        assert_eq!(transposed.sizes(), [num_polynomials, height]);
+        for row in 0..height {
+            for poly in 0..num_polynomials {
+                assert_eq!(
+                    transposed.as_slice()[poly * height + row],
+                    mle.guts().as_slice()[row * num_polynomials + poly]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_linear_combination() {
+        let mut rng = thread_rng();
+        let mle = Mle::<BabyBear>::rand(&mut rng, 3, 4);
+        let challenge = rng.gen::<EF>();
+        let combined = mle.random_linear_combination(challenge);
+        assert_eq!(combined.num_polynomials(), 1);
+        assert_eq!(combined.num_variables(), 4);
+
+        let point = (0..4).map(|_| rng.gen::<EF>()).collect::<Point<EF>>();
+        let evals = mle.blocking_eval_at(&point);
+        let expected =
+            (0..3).map(|i| evals[i]).zip(challenge.powers()).map(|(e, c)| e * c).sum::<EF>();
+        assert_eq!(combined.blocking_eval_at(&point)[0], expected);
+    }
+
+    #[test]
+    fn test_full_geq_usize_matches_full_geq() {
+        let mut rng = thread_rng();
+        let point = (0..5).map(|_| rng.gen::<EF>()).collect::<Point<EF>>();
+        for _ in 0..10 {
+            let threshold = rng.gen_range(0..1 << 5);
+            assert_eq!(
+                full_geq_usize(threshold, &point),
+                full_geq(&Point::<BabyBear>::from_usize(threshold, 5), &point)
+            );
+        }
+        for threshold in [0, 1, 31, 32] {
+            let geq = partial_geq_iter::<BabyBear>(threshold, 5).collect::<Mle<BabyBear>>();
+            assert_eq!(full_geq_usize(threshold, &point), geq.blocking_eval_at(&point)[0]);
+        }
+    }
+
+    #[test]
+    fn test_mle_eval_from_exact_iter() {
+        let mut rng = thread_rng();
+        let values = (0..7).map(|_| rng.gen::<EF>()).collect::<Vec<_>>();
+        let exact = MleEval::from_exact_iter(values.iter().copied());
+        assert_eq!(exact, values.iter().copied().collect::<MleEval<EF>>());
+        assert_eq!(exact.to_vec(), values);
+        assert!(MleEval::<EF>::from_exact_iter(std::iter::empty()).is_empty());
+    }
+
+    #[test]
+    fn test_try_full_lagrange_eval_and_geq_dimension_mismatch() {
+        let mut rng = thread_rng();
+        let point_1 = Point::<BabyBear>::from_usize(3, 4);
+        let point_2 = (0..5).map(|_| rng.gen::<EF>()).collect::<Point<EF>>();
+        assert_eq!(
+            Mle::try_full_lagrange_eval(&point_1, &point_2),
+            Err(PointDimensionMismatch(4, 5))
+        );
+        assert_eq!(try_full_geq(&point_1, &point_2), Err(PointDimensionMismatch(4, 5)));
+
+        let point_1 = Point::<BabyBear>::from_usize(3, 5);
+        assert_eq!(
+            Mle::try_full_lagrange_eval(&point_1, &point_2),
+            Ok(Mle::full_lagrange_eval(&point_1, &point_2))
+        );
+        assert_eq!(try_full_geq(&point_1, &point_2), Ok(full_geq(&point_1, &point_2)));
+    }
+
+    #[test]
+    fn test_try_full_lagrange_eval_ext_dimension_mismatch() {
+        let mut rng = thread_rng();
+        let point_1 = (0..4).map(|_| rng.gen::<EF>()).collect::<Point<EF>>();
+        let point_2 = (0..5).map(|_| rng.gen::<EF>()).collect::<Point<EF>>();
+        assert_eq!(
+            try_full_lagrange_eval_ext(&point_1, &point_2),
+            Err(PointDimensionMismatch(4, 5))
+        );
+
+        let point_1 = (0..5).map(|_| rng.gen::<EF>()).collect::<Point<EF>>();
+        assert_eq!(
+            try_full_lagrange_eval_ext(&point_1, &point_2),
+            Ok(full_lagrange_eval_ext(&point_1, &point_2))
+        );
+    }
+
+    #[test]
+    fn test_full_lagrange_eval_ext_matches_mixed() {
+        let mut rng = thread_rng();
+        let point_2 = (0..5).map(|_| rng.gen::<EF>()).collect::<Point<EF>>();
+        for index in 0..(1 << 5) {
+            let boolean = Point::<BabyBear>::from_usize(index, 5);
+            let boolean_ext = boolean.iter().map(|&x| EF::from_base(x)).collect::<Point<EF>>();
+            assert_eq!(
+                full_lagrange_eval_ext(&boolean_ext, &point_2),
+                Mle::full_lagrange_eval(&boolean, &point_2)
+            );
+        }
+    }
+
+    #[test]
+    fn test_hypercube_iter_matches_par_iter() {
+        let mle = Mle::<BabyBear>::rand(&mut thread_rng(), 3, 6);
+        let sequential = mle.hypercube_iter().collect::<Vec<_>>();
+        let parallel = mle.hypercube_par_iter().collect::<Vec<_>>();
+        assert_eq!(sequential, parallel);
+    }
+
+    #[test]
+    fn test_eq_up_to_poly_permutation() {
+        let (height, num_polynomials) = (4, 3);
+        let mle = Mle::new((0..12u32).collect::<Tensor<u32>>().reshape([height, num_polynomials]));
+        let perm = [2, 0, 1];
+        let permuted = Mle::new(
+            mle.guts()
+                .as_slice()
+                .chunks_exact(num_polynomials)
+                .flat_map(|row| perm.map(|i| row[i]))
+                .collect::<Tensor<u32>>()
+                .reshape([height, num_polynomials]),
+        );
+        assert!(mle.eq_up_to_poly_permutation(&permuted, &perm));
+        assert!(mle.eq_up_to_poly_permutation(&mle, &[0, 1, 2]));
+
+        // Permutations that do not map `mle` to `permuted`.
+        assert!(!mle.eq_up_to_poly_permutation(&permuted, &[0, 1, 2]));
+        assert!(!mle.eq_up_to_poly_permutation(&permuted, &[1, 2, 0]));
+        // Invalid permutations.
+        assert!(!mle.eq_up_to_poly_permutation(&mle, &[0, 0, 1]));
+        assert!(!mle.eq_up_to_poly_permutation(&mle, &[0, 1, 3]));
+        // Length mismatches.
+        assert!(!mle.eq_up_to_poly_permutation(&permuted, &[2, 0]));
+        assert!(!mle.eq_up_to_poly_permutation(&permuted, &[2, 0, 1, 3]));
+        let reshaped = Mle::new(mle.guts().clone().reshape([6, 2]));
+        assert!(!mle.eq_up_to_poly_permutation(&reshaped, &perm));
+    }
+
+    #[test]
+    fn test_stacking_shape() {
+        let mle = Mle::<BabyBear>::rand(&mut thread_rng(), 3, 4);
+        assert_eq!(mle.stacking_shape(0), [1, 48]);
+        assert_eq!(mle.stacking_shape(2), [4, 12]);
+        assert_eq!(mle.stacking_shape(5), [32, 2]);
+        assert_eq!(mle.stacking_shape(6), [64, 1]);
+
+        let mle = Mle::new((0..15u32).collect::<Tensor<u32>>().reshape([5, 3]));
+        assert_eq!(mle.stacking_shape(2), [4, 4]);
+        assert_eq!(mle.stacking_shape(4), [16, 1]);
+    }
+
+    #[test]
+    fn test_assert_invariants() {
+        let mut mle = Mle::<BabyBear>::rand(&mut thread_rng(), 3, 4);
+        mle.assert_invariants();
+        *mle.guts_mut() =
+            (0..24).map(BabyBear::from_canonical_u32).collect::<Tensor<_>>().reshape([8, 3]);
+        mle.assert_invariants();
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "MLE guts must be two-dimensional")]
+    fn test_assert_invariants_corrupted() {
+        let mut mle = Mle::<BabyBear>::rand(&mut thread_rng(), 3, 4);
+        *mle.guts_mut() = mle.guts().clone().reshape([2, 8, 3]);
+        mle.assert_invariants();
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "MLE height 5 is not a power of two")]
+    fn test_assert_invariants_height_not_power_of_two() {
+        let mut mle = Mle::<BabyBear>::rand(&mut thread_rng(), 3, 4);
+        *mle.guts_mut() =
+            (0..15).map(BabyBear::from_canonical_u32).collect::<Tensor<_>>().reshape([5, 3]);
+        mle.assert_invariants();
+    }
+
+    #[test]
+    fn test_as_single_poly() {
+        let mle = Mle::new((0..8u32).collect::<Tensor<u32>>().reshape([8, 1]));
+        assert_eq!(mle.as_single_poly(), Some((0..8).collect::<Vec<_>>().as_slice()));
+
+        let mle = Mle::new((0..8u32).collect::<Tensor<u32>>().reshape([4, 2]));
+        assert_eq!(mle.as_single_poly(), None);
+    }
+
+    #[test]
+    fn test_memory_bytes() {
+        let mut values = Vec::<u32>::with_capacity(16);
+        values.extend(0..12);
+        let capacity = values.capacity();
+        let mle = Mle::new(Tensor::from(values).reshape([4, 3]));
+        assert_eq!(mle.memory_bytes(), capacity * 4);
+    }
+}
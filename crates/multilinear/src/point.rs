@@ -10,6 +10,9 @@ use hypercube_tensor::Tensor;
 use p3_field::AbstractField;
 use rand::{distributions::Standard, prelude::Distribution};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::partial_lagrange_blocking;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[derive_where(PartialEq, Eq; Buffer<T, A>)]
@@ -21,6 +24,23 @@ pub struct Point<T, A: Backend = CpuBackend> {
     values: Buffer<T, A>,
 }
 
+/// The error returned when two points that should have the same dimension do not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("point dimension mismatch: {0} != {1}")]
+pub struct PointDimensionMismatch(pub usize, pub usize);
+
+impl PointDimensionMismatch {
+    /// Checks that the two dimensions are equal.
+    #[inline]
+    pub fn check(dimension_1: usize, dimension_2: usize) -> Result<(), Self> {
+        if dimension_1 == dimension_2 {
+            Ok(())
+        } else {
+            Err(Self(dimension_1, dimension_2))
+        }
+    }
+}
+
 impl<T, A: Backend> Point<T, A> {
     #[inline]
     pub const fn new(values: Buffer<T, A>) -> Self {
@@ -112,6 +132,12 @@ impl<T> Point<T, CpuBackend> {
         self.values.iter()
     }
 
+    /// Returns the `i`-th coordinate of the point, or `None` if `i` is out of bounds.
+    #[inline]
+    pub fn coord(&self, i: usize) -> Option<&T> {
+        self.values.get(i)
+    }
+
     // Creates a bool hypercube point that is the big endian binary representation of `num`.
     pub fn from_usize(num: usize, dimension: usize) -> Self
     where
@@ -226,6 +252,33 @@ impl<T> Point<T, CpuBackend> {
     pub fn extend(&mut self, other: &Self) {
         self.values.extend_from_slice(&other.values);
     }
+
+    /// Appends `coords` to the back of the point.
+    #[inline]
+    pub fn extend_from_iter(&mut self, coords: impl IntoIterator<Item = T>) {
+        for coord in coords {
+            self.values.push(coord);
+        }
+    }
+
+    /// Shortens the point to its first `len` coordinates, dropping the ones at the back.
+    ///
+    /// Has no effect if `len` is at least the dimension of the point.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        self.values.truncate(len);
+    }
+
+    /// Returns the `2^dimension` eq (partial Lagrange) weights of the point.
+    ///
+    /// The entry at index `i` is `eq(b, self)`, where `b` is the big-endian bit decomposition of
+    /// `i`, i.e. the first coordinate of the point corresponds to the most significant bit.
+    pub fn eq_weights(&self) -> Vec<T>
+    where
+        T: AbstractField,
+    {
+        partial_lagrange_blocking(self).into_buffer().into_vec()
+    }
 }
 
 impl<T> From<Vec<T>> for Point<T, CpuBackend> {
@@ -269,3 +322,62 @@ impl<T, A: Backend> HasBackend for Point<T, A> {
         self.values.allocator()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::extension::BinomialExtensionField;
+    use rand::{thread_rng, Rng};
+
+    use crate::Mle;
+
+    use super::*;
+
+    type EF = BinomialExtensionField<BabyBear, 4>;
+
+    #[test]
+    fn test_eq_weights() {
+        let mut rng = thread_rng();
+        let point = (0..4).map(|_| rng.gen::<EF>()).collect::<Point<EF>>();
+        let weights = point.eq_weights();
+        assert_eq!(weights.len(), 1 << point.dimension());
+        assert_eq!(weights, partial_lagrange_blocking(&point).as_slice());
+        for index in [0, 5, 15] {
+            let boolean = Point::<BabyBear>::from_usize(index, 4);
+            assert_eq!(weights[index], Mle::full_lagrange_eval(&boolean, &point));
+        }
+    }
+
+    #[test]
+    fn test_coord() {
+        let point = Point::<u32>::from(vec![1, 2, 3]);
+        assert_eq!(point.coord(0), Some(&1));
+        assert_eq!(point.coord(2), Some(&3));
+        assert_eq!(point.coord(3), None);
+        assert_eq!(Point::<u32>::from(vec![]).coord(0), None);
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut point = Point::<u32>::from(vec![1, 2]);
+        point.extend(&Point::from(vec![3, 4]));
+        assert_eq!(point.to_vec(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_extend_from_iter() {
+        let mut point = Point::<u32>::from(vec![1, 2]);
+        point.extend_from_iter([3, 4]);
+        point.extend_from_iter(std::iter::empty());
+        assert_eq!(point.to_vec(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut point = Point::<u32>::from(vec![1, 2, 3]);
+        point.truncate(5);
+        assert_eq!(point.dimension(), 3);
+        point.truncate(1);
+        assert_eq!(point.to_vec(), vec![1]);
+    }
+}
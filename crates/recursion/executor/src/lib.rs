@@ -10,13 +10,15 @@ pub use public_values::PV_DIGEST_NUM_WORDS;
 // Avoid triggering annoying branch of thiserror derive macro.
 pub use block::Block;
 pub use opcode::*;
-use p3_field::PrimeField64;
+use p3_field::{Field, PrimeField64};
 pub use public_values::{
-    RecursionPublicValues, NUM_PV_ELMS_TO_HASH, POSEIDON_NUM_WORDS, RECURSIVE_PROOF_NUM_PV_ELTS,
+    CompletenessError, RecursionPublicValues, RecursionPublicValuesBuilder, NUM_PV_ELMS_TO_HASH,
+    POSEIDON_NUM_WORDS, RECURSIVE_PROOF_NUM_PV_ELTS,
 };
 use serde::{Deserialize, Serialize};
 use sp1_derive::AlignedBorrow;
 use std::fmt::Debug;
+use thiserror::Error;
 
 /// The width of the Poseidon2 permutation.
 pub const PERMUTATION_WIDTH: usize = 16;
@@ -42,6 +44,34 @@ impl<F: PrimeField64> Address<F> {
     pub fn as_usize(&self) -> usize {
         self.0.as_canonical_u64() as usize
     }
+
+    /// The address immediately after this one.
+    #[inline]
+    #[track_caller]
+    pub fn next(&self) -> Self {
+        self.offset(1)
+    }
+
+    /// The address `delta` slots away from this one.
+    ///
+    /// The result is computed in the field; debug builds check that it does not wrap around.
+    #[inline]
+    #[track_caller]
+    pub fn offset(&self, delta: i64) -> Self {
+        let target = self.0.as_canonical_u64() as i128 + delta as i128;
+        debug_assert!(
+            (0..F::ORDER_U64 as i128).contains(&target),
+            "address {} offset by {} wraps around the field",
+            self.0.as_canonical_u64(),
+            delta
+        );
+        let magnitude = F::from_wrapped_u64(delta.unsigned_abs());
+        if delta >= 0 {
+            Self(self.0 + magnitude)
+        } else {
+            Self(self.0 - magnitude)
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -102,6 +132,23 @@ pub struct SelectIo<V> {
     pub in2: V,
 }
 
+impl<F: Field> SelectIo<F> {
+    /// Returns whether the outputs are the inputs selected by `bit`, as constrained by the select
+    /// chip.
+    ///
+    /// The bit must be boolean. A zero bit passes the inputs through (`out1 = in1`, `out2 = in2`)
+    /// and a one bit swaps them (`out1 = in2`, `out2 = in1`).
+    pub fn is_consistent(&self) -> bool {
+        if self.bit == F::zero() {
+            self.out1 == self.in1 && self.out2 == self.in2
+        } else if self.bit == F::one() {
+            self.out1 == self.in2 && self.out2 == self.in1
+        } else {
+            false
+        }
+    }
+}
+
 /// The inputs and outputs to the operations for prefix sum checks.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PrefixSumChecksIo<V> {
@@ -113,6 +160,43 @@ pub struct PrefixSumChecksIo<V> {
     pub field_accs: Vec<V>,
 }
 
+/// An error indicating that the operands of a prefix sum checks operation have inconsistent lengths.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Error)]
+pub enum PrefixSumShapeError {
+    #[error("x1 has length {x1} but x2 has length {x2}")]
+    InputLengthMismatch { x1: usize, x2: usize },
+    #[error("expected {expected} accumulators, got {actual}")]
+    AccsLengthMismatch { expected: usize, actual: usize },
+    #[error("expected {expected} field accumulators, got {actual}")]
+    FieldAccsLengthMismatch { expected: usize, actual: usize },
+}
+
+impl<V> PrefixSumChecksIo<V> {
+    /// Checks that the operand vectors have consistent lengths.
+    ///
+    /// Every row of the prefix sum checks chip reads `x1[i]` and `x2[i]` and writes `accs[i]` and
+    /// `field_accs[i]`, so all four vectors must have the same length.
+    pub fn validate_lengths(&self) -> Result<(), PrefixSumShapeError> {
+        let len = self.x1.len();
+        if self.x2.len() != len {
+            return Err(PrefixSumShapeError::InputLengthMismatch { x1: len, x2: self.x2.len() });
+        }
+        if self.accs.len() != len {
+            return Err(PrefixSumShapeError::AccsLengthMismatch {
+                expected: len,
+                actual: self.accs.len(),
+            });
+        }
+        if self.field_accs.len() != len {
+            return Err(PrefixSumShapeError::FieldAccsLengthMismatch {
+                expected: len,
+                actual: self.field_accs.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BatchFRIIo<V> {
     pub ext_single: BatchFRIExtSingleIo<Block<V>>,
@@ -141,3 +225,77 @@ pub struct BatchFRIExtVecIo<V> {
 pub struct BatchFRIBaseVecIo<V> {
     pub p_at_x: V,
 }
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+
+    use super::*;
+
+    fn io(x1: usize, x2: usize, accs: usize, field_accs: usize) -> PrefixSumChecksIo<u32> {
+        PrefixSumChecksIo {
+            zero: 0,
+            one: 1,
+            x1: vec![0; x1],
+            x2: vec![0; x2],
+            accs: vec![0; accs],
+            field_accs: vec![0; field_accs],
+        }
+    }
+
+    #[test]
+    fn test_validate_lengths() {
+        assert_eq!(io(0, 0, 0, 0).validate_lengths(), Ok(()));
+        assert_eq!(io(3, 3, 3, 3).validate_lengths(), Ok(()));
+        assert_eq!(
+            io(3, 2, 3, 3).validate_lengths(),
+            Err(PrefixSumShapeError::InputLengthMismatch { x1: 3, x2: 2 })
+        );
+        assert_eq!(
+            io(3, 3, 4, 3).validate_lengths(),
+            Err(PrefixSumShapeError::AccsLengthMismatch { expected: 3, actual: 4 })
+        );
+        assert_eq!(
+            io(3, 3, 3, 0).validate_lengths(),
+            Err(PrefixSumShapeError::FieldAccsLengthMismatch { expected: 3, actual: 0 })
+        );
+    }
+
+    #[test]
+    fn test_address_stepping() {
+        let max = BabyBear::ORDER_U64 as usize - 1;
+        let address = Address(BabyBear::from_canonical_usize(max - 1));
+        assert_eq!(address.next().as_usize(), max);
+        assert_eq!(address.offset(-5).as_usize(), max - 6);
+        assert_eq!(Address(BabyBear::zero()).next().as_usize(), 1);
+        assert_eq!(Address(BabyBear::from_canonical_u32(5)).offset(-5).as_usize(), 0);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "wraps around the field")]
+    fn test_address_next_wraps_around() {
+        Address(BabyBear::from_canonical_usize(BabyBear::ORDER_U64 as usize - 1)).next();
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "wraps around the field")]
+    fn test_address_offset_below_zero() {
+        Address(BabyBear::one()).offset(-2);
+    }
+
+    #[test]
+    fn test_select_io_is_consistent() {
+        let [a, b] = [BabyBear::from_canonical_u32(3), BabyBear::from_canonical_u32(7)];
+        let select = |bit: BabyBear, out1, out2| SelectIo { bit, out1, out2, in1: a, in2: b };
+
+        assert!(select(BabyBear::zero(), a, b).is_consistent());
+        assert!(!select(BabyBear::zero(), b, a).is_consistent());
+        assert!(select(BabyBear::one(), b, a).is_consistent());
+        assert!(!select(BabyBear::one(), a, b).is_consistent());
+        assert!(!select(BabyBear::two(), a, b).is_consistent());
+        assert!(!select(BabyBear::two(), b, a).is_consistent());
+    }
+}
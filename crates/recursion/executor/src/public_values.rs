@@ -1,16 +1,20 @@
 use core::fmt::Debug;
-use hypercube_stark::{septic_digest::SepticDigest, Word, PROOF_MAX_NUM_PVS};
+use hypercube_stark::{
+    septic_curve::SepticCurve, septic_digest::SepticDigest, septic_extension::SepticExtension,
+    Word, PROOF_MAX_NUM_PVS,
+};
 use hypercube_utils::indices_arr;
 use p3_challenger::DuplexChallenger;
-use p3_field::PrimeField32;
+use p3_field::{AbstractField, Field, PrimeField32};
 use p3_symmetric::CryptographicPermutation;
 use serde::{Deserialize, Serialize};
 use sp1_derive::AlignedBorrow;
 use static_assertions::const_assert_eq;
 use std::{
-    borrow::BorrowMut,
+    borrow::{Borrow, BorrowMut},
     mem::{size_of, transmute, MaybeUninit},
 };
+use thiserror::Error;
 
 use crate::{DIGEST_SIZE, HASH_RATE, PERMUTATION_WIDTH};
 
@@ -37,7 +41,7 @@ pub const NUM_PV_ELMS_TO_HASH: usize = RECURSION_PUBLIC_VALUES_COL_MAP.digest[0]
 // sp1_core should be set to `RECURSIVE_PROOF_NUM_PV_ELTS`.
 const_assert_eq!(RECURSIVE_PROOF_NUM_PV_ELTS, PROOF_MAX_NUM_PVS);
 
-#[derive(AlignedBorrow, Serialize, Deserialize, Clone, Copy, Default, Debug)]
+#[derive(AlignedBorrow, Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
 #[repr(C)]
 pub struct ChallengerPublicValues<T> {
     pub sponge_state: [T; PERMUTATION_WIDTH],
@@ -72,6 +76,30 @@ impl<T: Clone> ChallengerPublicValues<T> {
             ret
         }
     }
+
+    /// Returns the names of the fields in which `self` and `other` differ.
+    pub fn diff(&self, other: &Self) -> Vec<&'static str>
+    where
+        T: PartialEq,
+    {
+        let mut fields = Vec::new();
+        if self.sponge_state != other.sponge_state {
+            fields.push("sponge_state");
+        }
+        if self.num_inputs != other.num_inputs {
+            fields.push("num_inputs");
+        }
+        if self.input_buffer != other.input_buffer {
+            fields.push("input_buffer");
+        }
+        if self.num_outputs != other.num_outputs {
+            fields.push("num_outputs");
+        }
+        if self.output_buffer != other.output_buffer {
+            fields.push("output_buffer");
+        }
+        fields
+    }
 }
 
 /// The PublicValues struct is used to store all of a reduce proof's public values.
@@ -152,6 +180,275 @@ impl<F: Copy> RecursionPublicValues<F> {
     }
 }
 
+impl<F: Copy> RecursionPublicValues<F> {
+    /// Flattens the public values into their field elements, without going through the
+    /// [AlignedBorrow] layout.
+    ///
+    /// The elements are laid out in field declaration order, each array in index order:
+    /// `committed_value_digest` (word by word), `deferred_proofs_digest`, `start_pc`, `next_pc`,
+    /// `start_shard`, `next_shard`, `start_execution_shard`, `next_execution_shard`,
+    /// `previous_init_addr_word`, `last_init_addr_word`, `previous_finalize_addr_word`,
+    /// `last_finalize_addr_word`, `start_reconstruct_deferred_digest`,
+    /// `end_reconstruct_deferred_digest`, `sp1_vk_digest`, `vk_root`, `global_cumulative_sum` (the
+    /// `x` coordinate then the `y` coordinate), `is_complete`, `exit_code` and `digest`. This
+    /// matches [Self::as_array].
+    pub fn flatten(&self) -> [F; RECURSIVE_PROOF_NUM_PV_ELTS] {
+        let mut values = Vec::with_capacity(RECURSIVE_PROOF_NUM_PV_ELTS);
+        values.extend(self.committed_value_digest.iter().flatten());
+        values.extend(self.deferred_proofs_digest);
+        values.extend([
+            self.start_pc,
+            self.next_pc,
+            self.start_shard,
+            self.next_shard,
+            self.start_execution_shard,
+            self.next_execution_shard,
+        ]);
+        values.extend(self.previous_init_addr_word.0);
+        values.extend(self.last_init_addr_word.0);
+        values.extend(self.previous_finalize_addr_word.0);
+        values.extend(self.last_finalize_addr_word.0);
+        values.extend(self.start_reconstruct_deferred_digest);
+        values.extend(self.end_reconstruct_deferred_digest);
+        values.extend(self.sp1_vk_digest);
+        values.extend(self.vk_root);
+        values.extend(self.global_cumulative_sum.0.x.0);
+        values.extend(self.global_cumulative_sum.0.y.0);
+        values.extend([self.is_complete, self.exit_code]);
+        values.extend(self.digest);
+        values.try_into().unwrap_or_else(|_| unreachable!("public values length mismatch"))
+    }
+
+    /// Reads public values from field elements laid out as in [Self::flatten].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` does not have exactly [RECURSIVE_PROOF_NUM_PV_ELTS] elements.
+    #[track_caller]
+    pub fn from_flat(values: &[F]) -> Self {
+        assert_eq!(values.len(), RECURSIVE_PROOF_NUM_PV_ELTS, "wrong number of public values");
+        let mut values = values.iter().copied();
+        let mut next = || values.next().unwrap();
+        let committed_value_digest = core::array::from_fn(|_| core::array::from_fn(|_| next()));
+        let deferred_proofs_digest = core::array::from_fn(|_| next());
+        let start_pc = next();
+        let next_pc = next();
+        let start_shard = next();
+        let next_shard = next();
+        let start_execution_shard = next();
+        let next_execution_shard = next();
+        let previous_init_addr_word = Word(core::array::from_fn(|_| next()));
+        let last_init_addr_word = Word(core::array::from_fn(|_| next()));
+        let previous_finalize_addr_word = Word(core::array::from_fn(|_| next()));
+        let last_finalize_addr_word = Word(core::array::from_fn(|_| next()));
+        let start_reconstruct_deferred_digest = core::array::from_fn(|_| next());
+        let end_reconstruct_deferred_digest = core::array::from_fn(|_| next());
+        let sp1_vk_digest = core::array::from_fn(|_| next());
+        let vk_root = core::array::from_fn(|_| next());
+        let x = SepticExtension(core::array::from_fn(|_| next()));
+        let y = SepticExtension(core::array::from_fn(|_| next()));
+        let global_cumulative_sum = SepticDigest(SepticCurve { x, y });
+        let is_complete = next();
+        let exit_code = next();
+        let digest = core::array::from_fn(|_| next());
+        Self {
+            committed_value_digest,
+            deferred_proofs_digest,
+            start_pc,
+            next_pc,
+            start_shard,
+            next_shard,
+            start_execution_shard,
+            next_execution_shard,
+            previous_init_addr_word,
+            last_init_addr_word,
+            previous_finalize_addr_word,
+            last_finalize_addr_word,
+            start_reconstruct_deferred_digest,
+            end_reconstruct_deferred_digest,
+            sp1_vk_digest,
+            vk_root,
+            global_cumulative_sum,
+            is_complete,
+            exit_code,
+            digest,
+        }
+    }
+}
+
+impl<F: AbstractField + Copy> RecursionPublicValues<F> {
+    /// Returns public values with every element set to zero.
+    pub fn zeroed() -> Self {
+        let values = [F::zero(); RECURSIVE_PROOF_NUM_PV_ELTS];
+        let pv: &RecursionPublicValues<F> = values.as_slice().borrow();
+        *pv
+    }
+
+    /// Returns a builder starting from [Self::zeroed].
+    pub fn builder() -> RecursionPublicValuesBuilder<F> {
+        RecursionPublicValuesBuilder { values: Self::zeroed() }
+    }
+}
+
+/// The ways in which public values claiming a complete execution can be inconsistent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Error)]
+pub enum CompletenessError {
+    #[error("is_complete is not boolean")]
+    NotBoolean,
+    #[error("next_pc is not zero")]
+    NonZeroNextPc,
+    #[error("start_shard is not one")]
+    StartShardNotOne,
+    #[error("next_shard is one")]
+    NextShardIsOne,
+    #[error("start_execution_shard is not one")]
+    StartExecutionShardNotOne,
+    #[error("next_execution_shard is one")]
+    NextExecutionShardIsOne,
+    #[error("previous_init_addr_word is not zero")]
+    NonZeroPreviousInitAddr,
+    #[error("previous_finalize_addr_word is not zero")]
+    NonZeroPreviousFinalizeAddr,
+    #[error("start_reconstruct_deferred_digest is not zero")]
+    NonZeroStartDeferredDigest,
+    #[error("end_reconstruct_deferred_digest does not match deferred_proofs_digest")]
+    DeferredDigestMismatch,
+    #[error("global_cumulative_sum is not the zero digest")]
+    NonZeroCumulativeSum,
+}
+
+impl<F: Field> RecursionPublicValues<F> {
+    /// Evaluates every condition that a proof of a complete execution imposes on its public
+    /// values, paired with the error reported when it does not hold.
+    ///
+    /// This is the single list of completeness conditions, in the order they are checked by
+    /// [Self::is_complete_consistent]: the execution must start at the first shard, halt, start
+    /// with empty memory initialization and finalization, consume exactly the deferred proofs it
+    /// commits to, and balance the global lookup bus.
+    pub fn completeness_conditions(&self) -> [(bool, CompletenessError); 10] {
+        [
+            (self.next_pc.is_zero(), CompletenessError::NonZeroNextPc),
+            (self.start_shard.is_one(), CompletenessError::StartShardNotOne),
+            (!self.next_shard.is_one(), CompletenessError::NextShardIsOne),
+            (self.start_execution_shard.is_one(), CompletenessError::StartExecutionShardNotOne),
+            (!self.next_execution_shard.is_one(), CompletenessError::NextExecutionShardIsOne),
+            (
+                self.previous_init_addr_word.0.iter().all(F::is_zero),
+                CompletenessError::NonZeroPreviousInitAddr,
+            ),
+            (
+                self.previous_finalize_addr_word.0.iter().all(F::is_zero),
+                CompletenessError::NonZeroPreviousFinalizeAddr,
+            ),
+            (
+                self.start_reconstruct_deferred_digest.iter().all(F::is_zero),
+                CompletenessError::NonZeroStartDeferredDigest,
+            ),
+            (
+                self.end_reconstruct_deferred_digest == self.deferred_proofs_digest,
+                CompletenessError::DeferredDigestMismatch,
+            ),
+            (
+                self.global_cumulative_sum == SepticDigest::zero(),
+                CompletenessError::NonZeroCumulativeSum,
+            ),
+        ]
+    }
+
+    /// Checks the constraints that a proof of a complete execution imposes on its public values.
+    ///
+    /// If `is_complete` is zero there is nothing to check. Otherwise every condition of
+    /// [Self::completeness_conditions] must hold.
+    pub fn is_complete_consistent(&self) -> Result<(), CompletenessError> {
+        if self.is_complete.is_zero() {
+            return Ok(());
+        }
+        if !self.is_complete.is_one() {
+            return Err(CompletenessError::NotBoolean);
+        }
+        match self.completeness_conditions().into_iter().find(|(holds, _)| !holds) {
+            Some((_, error)) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A builder for [RecursionPublicValues], starting from all-zero values.
+#[derive(Clone, Copy, Debug)]
+pub struct RecursionPublicValuesBuilder<T> {
+    values: RecursionPublicValues<T>,
+}
+
+impl<T> RecursionPublicValuesBuilder<T> {
+    pub fn committed_value_digest(mut self, digest: [[T; 4]; PV_DIGEST_NUM_WORDS]) -> Self {
+        self.values.committed_value_digest = digest;
+        self
+    }
+
+    pub fn deferred_proofs_digest(mut self, digest: [T; POSEIDON_NUM_WORDS]) -> Self {
+        self.values.deferred_proofs_digest = digest;
+        self
+    }
+
+    pub fn pc(mut self, start_pc: T, next_pc: T) -> Self {
+        self.values.start_pc = start_pc;
+        self.values.next_pc = next_pc;
+        self
+    }
+
+    pub fn shard(mut self, start_shard: T, next_shard: T) -> Self {
+        self.values.start_shard = start_shard;
+        self.values.next_shard = next_shard;
+        self
+    }
+
+    pub fn execution_shard(mut self, start_execution_shard: T, next_execution_shard: T) -> Self {
+        self.values.start_execution_shard = start_execution_shard;
+        self.values.next_execution_shard = next_execution_shard;
+        self
+    }
+
+    pub fn sp1_vk_digest(mut self, digest: [T; DIGEST_SIZE]) -> Self {
+        self.values.sp1_vk_digest = digest;
+        self
+    }
+
+    pub fn vk_root(mut self, vk_root: [T; DIGEST_SIZE]) -> Self {
+        self.values.vk_root = vk_root;
+        self
+    }
+
+    pub fn global_cumulative_sum(mut self, sum: SepticDigest<T>) -> Self {
+        self.values.global_cumulative_sum = sum;
+        self
+    }
+
+    pub fn is_complete(mut self, is_complete: T) -> Self {
+        self.values.is_complete = is_complete;
+        self
+    }
+
+    pub fn exit_code(mut self, exit_code: T) -> Self {
+        self.values.exit_code = exit_code;
+        self
+    }
+
+    pub fn digest(mut self, digest: [T; DIGEST_SIZE]) -> Self {
+        self.values.digest = digest;
+        self
+    }
+
+    /// Applies an arbitrary update, for fields without a dedicated setter.
+    pub fn with(mut self, f: impl FnOnce(&mut RecursionPublicValues<T>)) -> Self {
+        f(&mut self.values);
+        self
+    }
+
+    pub fn build(self) -> RecursionPublicValues<T> {
+        self.values
+    }
+}
+
 impl<T: Copy> IntoIterator for RecursionPublicValues<T> {
     type Item = T;
     type IntoIter = std::array::IntoIter<T, RECURSIVE_PROOF_NUM_PV_ELTS>;
@@ -169,3 +466,124 @@ impl<T: Copy> IntoIterator for ChallengerPublicValues<T> {
         self.as_array().into_iter()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p3_baby_bear::BabyBear;
+
+    fn complete_public_values() -> RecursionPublicValues<BabyBear> {
+        RecursionPublicValues::builder()
+            .pc(BabyBear::from_canonical_u32(0x2000), BabyBear::zero())
+            .shard(BabyBear::one(), BabyBear::from_canonical_u32(4))
+            .execution_shard(BabyBear::one(), BabyBear::from_canonical_u32(3))
+            .global_cumulative_sum(SepticDigest::zero())
+            .is_complete(BabyBear::one())
+            .build()
+    }
+
+    #[test]
+    fn test_zeroed_is_all_zero() {
+        let public_values = RecursionPublicValues::<BabyBear>::zeroed();
+        assert_eq!(public_values.as_array(), [BabyBear::zero(); RECURSIVE_PROOF_NUM_PV_ELTS]);
+    }
+
+    #[test]
+    fn test_builder_sets_fields() {
+        let public_values = RecursionPublicValues::<BabyBear>::builder()
+            .pc(BabyBear::one(), BabyBear::two())
+            .is_complete(BabyBear::one())
+            .build();
+        assert_eq!(public_values.start_pc, BabyBear::one());
+        assert_eq!(public_values.next_pc, BabyBear::two());
+        assert_eq!(public_values.is_complete, BabyBear::one());
+        assert_eq!(public_values.start_shard, BabyBear::zero());
+    }
+
+    #[test]
+    fn test_flatten_round_trip() {
+        let values: [BabyBear; RECURSIVE_PROOF_NUM_PV_ELTS] =
+            core::array::from_fn(BabyBear::from_canonical_usize);
+        let public_values = RecursionPublicValues::from_flat(&values);
+        assert_eq!(public_values.flatten(), values);
+        // The explicit layout agrees with the `AlignedBorrow` one.
+        assert_eq!(public_values.as_array(), values);
+
+        let public_values = complete_public_values();
+        assert_eq!(public_values.flatten(), public_values.as_array());
+        assert_eq!(
+            RecursionPublicValues::from_flat(&public_values.flatten()).as_array(),
+            public_values.as_array()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "wrong number of public values")]
+    fn test_from_flat_wrong_length() {
+        RecursionPublicValues::from_flat(&[BabyBear::zero(); RECURSIVE_PROOF_NUM_PV_ELTS - 1]);
+    }
+
+    #[test]
+    fn test_complete_public_values_are_consistent() {
+        assert_eq!(complete_public_values().is_complete_consistent(), Ok(()));
+    }
+
+    #[test]
+    fn test_incomplete_public_values_are_not_checked() {
+        let public_values = RecursionPublicValues::<BabyBear>::builder()
+            .pc(BabyBear::one(), BabyBear::two())
+            .build();
+        assert_eq!(public_values.is_complete_consistent(), Ok(()));
+    }
+
+    #[test]
+    fn test_inconsistent_public_values() {
+        let mut public_values = complete_public_values();
+        public_values.is_complete = BabyBear::two();
+        assert_eq!(public_values.is_complete_consistent(), Err(CompletenessError::NotBoolean));
+
+        let mut public_values = complete_public_values();
+        public_values.next_pc = BabyBear::one();
+        assert_eq!(public_values.is_complete_consistent(), Err(CompletenessError::NonZeroNextPc));
+
+        let mut public_values = complete_public_values();
+        public_values.next_shard = BabyBear::one();
+        assert_eq!(public_values.is_complete_consistent(), Err(CompletenessError::NextShardIsOne));
+
+        let mut public_values = complete_public_values();
+        public_values.next_execution_shard = BabyBear::one();
+        assert_eq!(
+            public_values.is_complete_consistent(),
+            Err(CompletenessError::NextExecutionShardIsOne)
+        );
+
+        let mut public_values = complete_public_values();
+        public_values.deferred_proofs_digest[0] = BabyBear::one();
+        assert_eq!(
+            public_values.is_complete_consistent(),
+            Err(CompletenessError::DeferredDigestMismatch)
+        );
+
+        let mut public_values = complete_public_values();
+        public_values.global_cumulative_sum = SepticDigest::starting_digest();
+        assert_eq!(
+            public_values.is_complete_consistent(),
+            Err(CompletenessError::NonZeroCumulativeSum)
+        );
+    }
+
+    #[test]
+    fn test_challenger_public_values_diff() {
+        let state = ChallengerPublicValues::<BabyBear>::default();
+        let mut other = state;
+        assert_eq!(state, other);
+        assert!(state.diff(&other).is_empty());
+
+        other.sponge_state[3] = BabyBear::one();
+        assert_ne!(state, other);
+        assert_eq!(state.diff(&other), ["sponge_state"]);
+
+        other.num_outputs = BabyBear::two();
+        assert_eq!(other.diff(&state), ["sponge_state", "num_outputs"]);
+    }
+}
@@ -2,7 +2,8 @@ pub mod constant;
 pub mod variable;
 
 pub use constant::MemoryConstChip;
-use hypercube_recursion_executor::Address;
+use hypercube_recursion_executor::{Address, MemAccessKind};
+use p3_field::PrimeField32;
 pub use variable::MemoryVarChip;
 
 use sp1_derive::AlignedBorrow;
@@ -20,5 +21,64 @@ pub struct MemoryAccessColsChips<F: Copy> {
     pub mult: F,
 }
 
+impl<F: PrimeField32> MemoryAccessColsChips<F> {
+    /// Creates a memory access, encoding the kind of access in the sign of the multiplicity.
+    pub fn new(addr: Address<F>, kind: MemAccessKind, magnitude: F) -> Self {
+        let mult = match kind {
+            MemAccessKind::Write => magnitude,
+            MemAccessKind::Read => -magnitude,
+        };
+        Self { addr, mult }
+    }
+
+    /// Decodes the kind of access from the sign of the multiplicity.
+    ///
+    /// A multiplicity is considered "negative" if its canonical representative lies in the upper
+    /// half of the field. A zero multiplicity is decoded as a write.
+    pub fn kind(&self) -> MemAccessKind {
+        if self.mult.as_canonical_u32() > F::ORDER_U32 / 2 {
+            MemAccessKind::Read
+        } else {
+            MemAccessKind::Write
+        }
+    }
+}
+
 /// Avoids cbindgen naming collisions.
 pub type MemoryAccessCols<F> = MemoryAccessColsChips<F>;
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+
+    use super::*;
+
+    #[test]
+    fn test_mem_access_kind_round_trip() {
+        let addr = Address(BabyBear::from_canonical_u32(7));
+        for magnitude in [1, 2, 1000, BabyBear::ORDER_U32 / 2] {
+            let magnitude = BabyBear::from_canonical_u32(magnitude);
+            for kind in [MemAccessKind::Read, MemAccessKind::Write] {
+                let access = MemoryAccessColsChips::new(addr, kind, magnitude);
+                assert_eq!(access.addr, addr);
+                assert_eq!(access.kind(), kind);
+            }
+            assert_eq!(
+                MemoryAccessColsChips::new(addr, MemAccessKind::Write, magnitude).mult,
+                magnitude
+            );
+            assert_eq!(
+                MemoryAccessColsChips::new(addr, MemAccessKind::Read, magnitude).mult,
+                -magnitude
+            );
+        }
+    }
+
+    #[test]
+    fn test_zero_multiplicity_is_write() {
+        let access =
+            MemoryAccessColsChips { addr: Address(BabyBear::one()), mult: BabyBear::zero() };
+        assert_eq!(access.kind(), MemAccessKind::Write);
+    }
+}
@@ -3,5 +3,5 @@ pub mod chips;
 mod machine;
 mod verify_compress;
 
-pub use machine::RecursionAir;
+pub use machine::{MissingChipError, RecursionAir, RecursionChipKind};
 pub use verify_compress::*;
@@ -1,8 +1,9 @@
-use hypercube_recursion_executor::D;
+use hypercube_recursion_executor::{D, RECURSIVE_PROOF_NUM_PV_ELTS};
 use hypercube_stark::{Chip, Machine, PROOF_MAX_NUM_PVS};
 use p3_field::{extension::BinomiallyExtendable, PrimeField32};
 
 use strum_macros::EnumDiscriminants;
+use thiserror::Error;
 
 use crate::chips::{
     alu_base::BaseAluChip,
@@ -16,6 +17,8 @@ use crate::chips::{
 };
 
 #[derive(sp1_derive::MachineAir, EnumDiscriminants)]
+#[strum_discriminants(name(RecursionChipKind))]
+#[strum_discriminants(derive(Hash, PartialOrd, Ord))]
 #[sp1_core_path = "hypercube_core_machine"]
 #[builder_path = "crate::builder::SP1RecursionAirBuilder<F = F>"]
 #[eval_trait_bound = "AB::Var: 'static"]
@@ -31,11 +34,62 @@ pub enum RecursionAir<F: PrimeField32 + BinomiallyExtendable<D>, const DEGREE: u
     PublicValues(PublicValuesChip),
 }
 
+/// An error returned when a chip selection is missing a chip that another selected chip needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("chip {chip:?} requires chip {requires:?}")]
+pub struct MissingChipError {
+    pub chip: RecursionChipKind,
+    pub requires: RecursionChipKind,
+}
+
+impl RecursionChipKind {
+    /// The chips that must be present in a machine containing this chip.
+    ///
+    /// Every chip other than the memory chips reads and writes memory, so it needs both of them
+    /// to balance its interactions.
+    pub fn dependencies(&self) -> &'static [RecursionChipKind] {
+        match self {
+            RecursionChipKind::MemoryConst | RecursionChipKind::MemoryVar => &[],
+            _ => &[RecursionChipKind::MemoryConst, RecursionChipKind::MemoryVar],
+        }
+    }
+}
+
 #[allow(dead_code)]
 impl<F: PrimeField32 + BinomiallyExtendable<D>, const DEGREE: usize> RecursionAir<F, DEGREE> {
     /// Get a machine with all chips, except the dummy chip.
     pub fn machine_wide_with_all_chips() -> Machine<F, Self> {
-        let chips = [
+        let chips = Self::all_chips().map(Chip::new).into_iter().collect::<Vec<_>>();
+
+        let machine = Machine::new(chips, PROOF_MAX_NUM_PVS);
+        machine
+            .validate_pv_layout(RECURSIVE_PROOF_NUM_PV_ELTS)
+            .expect("recursion machine public values layout mismatch");
+        machine
+    }
+
+    /// Get a machine with only the given chips, in the same order as
+    /// [Self::machine_wide_with_all_chips].
+    ///
+    /// Returns an error if a selected chip depends on a chip that was not selected.
+    pub fn with_chips(kinds: &[RecursionChipKind]) -> Result<Machine<F, Self>, MissingChipError> {
+        for kind in kinds {
+            if let Some(&requires) = kind.dependencies().iter().find(|dep| !kinds.contains(dep)) {
+                return Err(MissingChipError { chip: *kind, requires });
+            }
+        }
+
+        let chips = Self::all_chips()
+            .into_iter()
+            .filter(|chip| kinds.contains(&RecursionChipKind::from(chip)))
+            .map(Chip::new)
+            .collect::<Vec<_>>();
+
+        Ok(Machine::new(chips, PROOF_MAX_NUM_PVS))
+    }
+
+    fn all_chips() -> [Self; 8] {
+        [
             RecursionAir::MemoryConst(MemoryConstChip::default()),
             RecursionAir::MemoryVar(MemoryVarChip::default()),
             RecursionAir::BaseAlu(BaseAluChip),
@@ -45,10 +99,85 @@ impl<F: PrimeField32 + BinomiallyExtendable<D>, const DEGREE: usize> RecursionAi
             RecursionAir::Select(SelectChip),
             RecursionAir::PublicValues(PublicValuesChip),
         ]
-        .map(Chip::new)
-        .into_iter()
-        .collect::<Vec<_>>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+
+    use super::*;
+
+    type TestAir = RecursionAir<BabyBear, 3>;
+
+    fn kinds(machine: &Machine<BabyBear, TestAir>) -> Vec<RecursionChipKind> {
+        machine.chips().iter().map(|chip| RecursionChipKind::from(chip.air.as_ref())).collect()
+    }
+
+    #[test]
+    fn test_with_chips_minimal_machine() {
+        let selection = [
+            RecursionChipKind::BaseAlu,
+            RecursionChipKind::MemoryVar,
+            RecursionChipKind::MemoryConst,
+        ];
+        let machine = TestAir::with_chips(&selection).unwrap();
+        assert_eq!(
+            kinds(&machine),
+            [
+                RecursionChipKind::MemoryConst,
+                RecursionChipKind::MemoryVar,
+                RecursionChipKind::BaseAlu
+            ]
+        );
+
+        let memory_only = TestAir::with_chips(&[RecursionChipKind::MemoryConst]).unwrap();
+        assert_eq!(kinds(&memory_only), [RecursionChipKind::MemoryConst]);
+    }
+
+    #[test]
+    fn test_with_chips_missing_dependency() {
+        assert_eq!(
+            TestAir::with_chips(&[RecursionChipKind::BaseAlu, RecursionChipKind::MemoryConst])
+                .unwrap_err(),
+            MissingChipError {
+                chip: RecursionChipKind::BaseAlu,
+                requires: RecursionChipKind::MemoryVar
+            }
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+
+    use super::*;
+
+    #[test]
+    fn test_shape_descriptor() {
+        let descriptor =
+            RecursionAir::<BabyBear, 3>::machine_wide_with_all_chips().shape_descriptor();
+        let expected = [
+            ("MemoryConst", 12, 1),
+            ("MemoryVar", 4, 8),
+            ("BaseAlu", 32, 12),
+            ("ExtAlu", 32, 48),
+            ("Poseidon2WideDeg3", 49, 313),
+            ("PrefixSumChecks", 9, 19),
+            ("Select", 8, 5),
+            ("PublicValues", 10, 1),
+        ]
+        .map(|(name, preprocessed_width, main_width)| {
+            (name.to_string(), preprocessed_width, main_width)
+        });
+        assert_eq!(descriptor, expected);
 
-        Machine::new(chips, PROOF_MAX_NUM_PVS)
+        let json = serde_json::to_string(&descriptor).unwrap();
+        assert_eq!(
+            json,
+            r#"[["MemoryConst",12,1],["MemoryVar",4,8],["BaseAlu",32,12],["ExtAlu",32,48],["Poseidon2WideDeg3",49,313],["PrefixSumChecks",9,19],["Select",8,5],["PublicValues",10,1]]"#
+        );
+        assert_eq!(serde_json::from_str::<Vec<(String, usize, usize)>>(&json).unwrap(), descriptor);
     }
 }
@@ -1,13 +1,14 @@
 use std::borrow::Borrow;
 
 use hypercube_jagged::BabyBearPoseidon2;
-use hypercube_recursion_executor::{RecursionPublicValues, NUM_PV_ELMS_TO_HASH};
+use hypercube_recursion_executor::{CompletenessError, RecursionPublicValues, NUM_PV_ELMS_TO_HASH};
 use hypercube_stark::{
-    MachineConfig, MachineVerifyingKey, ShardProof, ShardVerifier, ShardVerifierError,
+    BabyBearShardVerifier, MachineConfig, MachineVerifyingKey, ShardProof, ShardVerifier,
+    ShardVerifierError,
 };
 use itertools::Itertools;
 use p3_baby_bear::BabyBear;
-use p3_field::AbstractField;
+use p3_field::{AbstractField, PrimeField32};
 use serde::{Deserialize, Serialize};
 use sp1_primitives::{io::SP1PublicValues, poseidon2_hash};
 use strum_macros::{EnumDiscriminants, EnumTryAs};
@@ -97,6 +98,9 @@ pub enum MachineVerifierError<C: MachineConfig> {
     /// There are too many shards.
     #[error("too many shards")]
     TooManyShards,
+    /// The program exited with a code outside the accepted set.
+    #[error("unexpected exit code: {0}")]
+    UnexpectedExitCode(u32),
 }
 
 /// Compute the digest of the public values.
@@ -115,7 +119,7 @@ pub fn assert_recursion_public_values_valid(public_values: &RecursionPublicValue
     }
 }
 
-fn verifier() -> ShardVerifier<BabyBearPoseidon2, CompressAir<BabyBear>> {
+fn verifier() -> BabyBearShardVerifier<CompressAir<BabyBear>> {
     let compress_log_blowup = 1;
     let compress_log_stacking_height = 20;
     let compress_max_log_row_count = 20;
@@ -129,11 +133,33 @@ fn verifier() -> ShardVerifier<BabyBearPoseidon2, CompressAir<BabyBear>> {
     )
 }
 
-/// Verify a compressed proof.
+/// Verify a compressed proof, returning its public values on success.
+///
+/// Only proofs of executions that exited with code zero are accepted, see
+/// [verify_compressed_with_exit_codes] to accept other exit codes.
 pub fn verify_compressed(
     proof: &SP1ReduceProof<BabyBearPoseidon2>,
     vk: &MachineVerifyingKey<BabyBearPoseidon2>,
-) -> Result<(), MachineVerifierError<BabyBearPoseidon2>> {
+) -> Result<RecursionPublicValues<BabyBear>, MachineVerifierError<BabyBearPoseidon2>> {
+    verify_compressed_with_exit_codes(proof, vk, &[0])
+}
+
+/// Verify a compressed proof whose exit code is one of `accepted_exit_codes`, returning its public
+/// values on success.
+pub fn verify_compressed_with_exit_codes(
+    proof: &SP1ReduceProof<BabyBearPoseidon2>,
+    vk: &MachineVerifyingKey<BabyBearPoseidon2>,
+    accepted_exit_codes: &[u32],
+) -> Result<RecursionPublicValues<BabyBear>, MachineVerifierError<BabyBearPoseidon2>> {
+    verify_compressed_inner(proof, vk, accepted_exit_codes, true)
+}
+
+fn verify_compressed_inner(
+    proof: &SP1ReduceProof<BabyBearPoseidon2>,
+    vk: &MachineVerifyingKey<BabyBearPoseidon2>,
+    accepted_exit_codes: &[u32],
+    require_complete: bool,
+) -> Result<RecursionPublicValues<BabyBear>, MachineVerifierError<BabyBearPoseidon2>> {
     let SP1ReduceProof { vk: compress_vk, proof } = proof;
     let verifier = verifier();
     let mut challenger = verifier.challenger();
@@ -146,7 +172,7 @@ pub fn verify_compressed(
     let public_values: &RecursionPublicValues<_> = proof.public_values.as_slice().borrow();
     assert_recursion_public_values_valid(public_values);
 
-    if public_values.is_complete != BabyBear::one() {
+    if require_complete && public_values.is_complete != BabyBear::one() {
         return Err(MachineVerifierError::InvalidPublicValues("is_complete is not 1"));
     }
 
@@ -156,5 +182,253 @@ pub fn verify_compressed(
         return Err(MachineVerifierError::InvalidPublicValues("sp1 vk hash mismatch"));
     }
 
-    Ok(())
+    // Verify that the program exited with an accepted code.
+    let exit_code = public_values.exit_code.as_canonical_u32();
+    if !accepted_exit_codes.contains(&exit_code) {
+        return Err(MachineVerifierError::UnexpectedExitCode(exit_code));
+    }
+
+    Ok(*public_values)
+}
+
+/// An error that occurs when verifying a chain of compressed proofs.
+#[derive(Debug, Error)]
+pub enum ChainError {
+    /// The chain contains no proofs.
+    #[error("empty proof chain")]
+    Empty,
+    /// A proof in the chain failed to verify.
+    #[error("proof {0} is invalid: {1}")]
+    InvalidProof(usize, MachineVerifierError<BabyBearPoseidon2>),
+    /// A proof does not start where the previous one ended.
+    #[error("proof {0} does not continue the previous proof: {1} mismatch")]
+    Discontinuity(usize, &'static str),
+    /// The chain as a whole does not prove a complete execution.
+    #[error("proof chain is not complete: {0}")]
+    Incomplete(CompletenessError),
+}
+
+/// Verify a chain of compressed proofs of consecutive shard ranges, returning the public values of
+/// the whole range on success.
+///
+/// Every proof is verified on its own without requiring it to be complete, and the public values
+/// are then combined with [aggregate_chain_public_values], which checks that the chain proves a
+/// complete execution.
+pub fn verify_compressed_chain(
+    proofs: &[SP1ReduceProof<BabyBearPoseidon2>],
+    vk: &MachineVerifyingKey<BabyBearPoseidon2>,
+) -> Result<RecursionPublicValues<BabyBear>, ChainError> {
+    let public_values = proofs
+        .iter()
+        .enumerate()
+        .map(|(i, proof)| {
+            verify_compressed_inner(proof, vk, &[0], false)
+                .map_err(|e| ChainError::InvalidProof(i, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    aggregate_chain_public_values(&public_values)
+}
+
+/// Combine the public values of consecutive shard ranges into the public values of the whole
+/// range.
+///
+/// Each range must start where the previous one ended (pc, shard, execution shard, memory
+/// addresses and deferred proofs digest). The result takes its starting state from the first range,
+/// sums the global cumulative sums and takes everything else from the last range. It is marked
+/// complete, must satisfy [RecursionPublicValues::is_complete_consistent], and has its digest
+/// recomputed.
+pub fn aggregate_chain_public_values(
+    public_values: &[RecursionPublicValues<BabyBear>],
+) -> Result<RecursionPublicValues<BabyBear>, ChainError> {
+    for (i, (prev, next)) in public_values.iter().tuple_windows().enumerate() {
+        let i = i + 1;
+        if prev.next_pc != next.start_pc {
+            return Err(ChainError::Discontinuity(i, "pc"));
+        }
+        if prev.next_shard != next.start_shard {
+            return Err(ChainError::Discontinuity(i, "shard"));
+        }
+        if prev.next_execution_shard != next.start_execution_shard {
+            return Err(ChainError::Discontinuity(i, "execution shard"));
+        }
+        if prev.last_init_addr_word != next.previous_init_addr_word {
+            return Err(ChainError::Discontinuity(i, "memory init address"));
+        }
+        if prev.last_finalize_addr_word != next.previous_finalize_addr_word {
+            return Err(ChainError::Discontinuity(i, "memory finalize address"));
+        }
+        if prev.end_reconstruct_deferred_digest != next.start_reconstruct_deferred_digest {
+            return Err(ChainError::Discontinuity(i, "deferred proofs digest"));
+        }
+    }
+
+    let (Some(first), Some(last)) = (public_values.first(), public_values.last()) else {
+        return Err(ChainError::Empty);
+    };
+    let mut aggregated = *last;
+    aggregated.start_pc = first.start_pc;
+    aggregated.start_shard = first.start_shard;
+    aggregated.start_execution_shard = first.start_execution_shard;
+    aggregated.previous_init_addr_word = first.previous_init_addr_word;
+    aggregated.previous_finalize_addr_word = first.previous_finalize_addr_word;
+    aggregated.start_reconstruct_deferred_digest = first.start_reconstruct_deferred_digest;
+    aggregated.global_cumulative_sum =
+        public_values.iter().map(|values| values.global_cumulative_sum).sum();
+    aggregated.is_complete = BabyBear::one();
+    aggregated.is_complete_consistent().map_err(ChainError::Incomplete)?;
+    aggregated.digest = recursion_public_values_digest(&aggregated);
+
+    Ok(aggregated)
+}
+
+#[cfg(test)]
+mod tests {
+    use hypercube_jagged::{JaggedPcsVerifierError, MachineJaggedPcsVerifier};
+    use hypercube_stark::septic_digest::SepticDigest;
+
+    use super::*;
+
+    fn segment(start_pc: u32, next_pc: u32, start_shard: u32) -> RecursionPublicValues<BabyBear> {
+        let f = BabyBear::from_canonical_u32;
+        RecursionPublicValues::builder()
+            .pc(f(start_pc), f(next_pc))
+            .shard(f(start_shard), f(start_shard + 1))
+            .execution_shard(f(start_shard), f(start_shard + 1))
+            .global_cumulative_sum(SepticDigest::zero())
+            .build()
+    }
+
+    #[test]
+    fn test_aggregate_valid_chain() {
+        let chain = [segment(0x2000, 0x3000, 1), segment(0x3000, 0, 2)];
+        let aggregated = aggregate_chain_public_values(&chain).unwrap();
+        assert_eq!(aggregated.start_pc, BabyBear::from_canonical_u32(0x2000));
+        assert_eq!(aggregated.next_pc, BabyBear::zero());
+        assert_eq!(aggregated.start_shard, BabyBear::one());
+        assert_eq!(aggregated.next_shard, BabyBear::from_canonical_u32(3));
+        assert_eq!(aggregated.is_complete, BabyBear::one());
+        assert_recursion_public_values_valid(&aggregated);
+    }
+
+    #[test]
+    fn test_aggregate_broken_pc_link() {
+        let chain = [segment(0x2000, 0x3000, 1), segment(0x3004, 0, 2)];
+        assert!(matches!(
+            aggregate_chain_public_values(&chain),
+            Err(ChainError::Discontinuity(1, "pc"))
+        ));
+    }
+
+    #[test]
+    fn test_aggregate_broken_shard_link() {
+        let chain = [segment(0x2000, 0x3000, 1), segment(0x3000, 0, 3)];
+        assert!(matches!(
+            aggregate_chain_public_values(&chain),
+            Err(ChainError::Discontinuity(1, "shard"))
+        ));
+    }
+
+    #[test]
+    fn test_aggregate_incomplete_chain() {
+        assert!(matches!(aggregate_chain_public_values(&[]), Err(ChainError::Empty)));
+
+        let chain = [segment(0x2000, 0x3000, 2), segment(0x3000, 0, 3)];
+        assert!(matches!(
+            aggregate_chain_public_values(&chain),
+            Err(ChainError::Incomplete(CompletenessError::StartShardNotOne))
+        ));
+    }
+
+    fn load_compressed_proof() -> SP1ReduceProof<BabyBearPoseidon2> {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../../proof.bin");
+        let file = std::fs::File::open(path).unwrap();
+        let proof: SP1ProofWithPublicValues = bincode::deserialize_from(file).unwrap();
+        *proof.proof.try_as_compressed().unwrap()
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let SP1ReduceProof { proof, .. } = load_compressed_proof();
+        let hash = proof.content_hash();
+        assert_eq!(hash, proof.clone().content_hash());
+        assert_eq!(hash, load_compressed_proof().proof.content_hash());
+
+        let mut tampered = proof;
+        tampered.public_values[0] += BabyBear::one();
+        assert_ne!(hash, tampered.content_hash());
+    }
+
+    /// Verifies the compressed shard proof after keeping only its FRI queries at positions `keep`.
+    fn verify_query_subset(keep: &[usize]) -> Result<(), ShardVerifierError<BabyBearPoseidon2>> {
+        let SP1ReduceProof { vk, mut proof } = load_compressed_proof();
+        let pcs_proof = &mut proof.evaluation_proof.stacked_pcs_proof.pcs_proof;
+        *pcs_proof = pcs_proof.with_query_subset(keep);
+
+        let mut verifier = verifier();
+        let basefold_verifier = &mut verifier.pcs_verifier.stacked_pcs_verifier.pcs_verifier;
+        *basefold_verifier =
+            basefold_verifier.clone().with_num_queries(keep.len(), pcs_proof).unwrap();
+
+        let mut challenger = verifier.challenger();
+        vk.observe_into(&mut challenger);
+        verifier.verify_shard(&vk, &proof, &mut challenger)
+    }
+
+    #[test]
+    fn test_query_prefix_verifies() {
+        verify_query_subset(&(0..20).collect::<Vec<_>>()).unwrap();
+    }
+
+    #[test]
+    fn test_query_non_prefix_rejected() {
+        assert!(verify_query_subset(&(1..21).collect::<Vec<_>>()).is_err());
+    }
+
+    #[test]
+    fn test_empty_shard_rejected() {
+        let SP1ReduceProof { vk, mut proof } = load_compressed_proof();
+        proof.shard_chips.clear();
+        proof.opened_values.chips.clear();
+
+        let verifier = verifier();
+        let mut challenger = verifier.challenger();
+        vk.observe_into(&mut challenger);
+        assert!(matches!(
+            verifier.verify_shard(&vk, &proof, &mut challenger),
+            Err(ShardVerifierError::EmptyShard)
+        ));
+    }
+
+    #[test]
+    fn test_empty_jagged_batch_rejected() {
+        let SP1ReduceProof { proof, .. } = load_compressed_proof();
+        let verifier = verifier();
+        let jagged_verifier =
+            MachineJaggedPcsVerifier::new(&verifier.pcs_verifier, vec![vec![], vec![0]]);
+        let result = jagged_verifier.verify_trusted_evaluations(
+            &[proof.main_commitment; 2],
+            proof.zerocheck_proof.point_and_eval.0.clone(),
+            &[],
+            &proof.evaluation_proof,
+            &mut verifier.challenger(),
+        );
+        assert!(matches!(result, Err(JaggedPcsVerifierError::EmptyBatch)));
+    }
+
+    #[test]
+    fn test_too_few_public_values_rejected() {
+        let SP1ReduceProof { vk, mut proof } = load_compressed_proof();
+        let verifier = verifier();
+        let num_pv_elts = verifier.machine.num_pv_elts();
+        proof.public_values.truncate(num_pv_elts - 1);
+
+        let mut challenger = verifier.challenger();
+        vk.observe_into(&mut challenger);
+        assert!(matches!(
+            verifier.verify_shard(&vk, &proof, &mut challenger),
+            Err(ShardVerifierError::InvalidPublicValues(expected, actual))
+                if expected == num_pv_elts && actual == num_pv_elts - 1
+        ));
+    }
 }
@@ -1,6 +1,8 @@
 use p3_air::BaseAir;
 use p3_field::Field;
 
+use super::MachineAirBuilder;
+
 // TODO: add Id type and also fn id()
 
 #[macro_export]
@@ -20,4 +22,15 @@ pub trait MachineAir<F: Field>: BaseAir<F> + 'static + Send + Sync {
     fn preprocessed_width(&self) -> usize {
         0
     }
+
+    /// Whether the AIR has constraints on the public values that the verifier checks directly.
+    fn has_public_constraints(&self) -> bool {
+        false
+    }
+
+    /// Evaluates the constraints that the AIR places on the public values alone.
+    ///
+    /// These constraints must not read the trace, since the verifier checks them without any
+    /// openings. Only called for AIRs with [`MachineAir::has_public_constraints`] set.
+    fn eval_public_values<AB: MachineAirBuilder<F = F>>(&self, _builder: &mut AB) {}
 }
@@ -6,7 +6,7 @@ use p3_matrix::dense::RowMajorMatrix;
 use p3_uni_stark::{get_max_constraint_degree, get_symbolic_constraints, SymbolicAirBuilder};
 
 use crate::{
-    air::{MachineAir, MultiTableAirBuilder, SP1AirBuilder},
+    air::{InteractionScope, MachineAir, MultiTableAirBuilder, SP1AirBuilder},
     log2_ceil_usize,
     lookup::{Interaction, InteractionBuilder, InteractionKind},
 };
@@ -139,6 +139,23 @@ where
         self.receives.iter().filter(|i| i.kind == kind).count()
     }
 
+    /// Returns the number of sends and receives in the given scope.
+    #[inline]
+    #[must_use]
+    pub fn num_interactions_in_scope(&self, scope: InteractionScope) -> usize {
+        self.sends.iter().chain(self.receives.iter()).filter(|i| i.scope == scope).count()
+    }
+
+    /// Returns whether the chip has a preprocessed trace.
+    #[inline]
+    #[must_use]
+    pub fn has_preprocessed(&self) -> bool
+    where
+        A: MachineAir<F>,
+    {
+        self.preprocessed_width() > 0
+    }
+
     /// Returns the cost of a row in the chip.
     #[inline]
     #[must_use]
@@ -47,6 +47,8 @@ pub struct GenericVerifierConstraintFolder<'a, F, EF, PubVar, Var, Expr> {
     pub accumulator: Expr,
     /// The public values.
     pub public_values: &'a [PubVar],
+    /// If set, the value of every asserted constraint is recorded here, in order, for debugging.
+    pub constraint_values: Option<Vec<Expr>>,
     /// The marker type.
     pub _marker: PhantomData<(F, EF)>,
 }
@@ -107,6 +109,9 @@ where
 
     fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
         let x: Expr = x.into();
+        if let Some(values) = self.constraint_values.as_mut() {
+            values.push(x.clone());
+        }
         self.accumulator *= self.alpha.into();
         self.accumulator += x;
     }
@@ -1,8 +1,10 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, ops::Deref};
 
 use hypercube_alloc::{Backend, CpuBackend};
 use hypercube_multilinear::{Mle, MleEval, Point};
 use hypercube_sumcheck::PartialSumcheckProof;
+use itertools::Itertools;
+use p3_field::Field;
 use serde::{Deserialize, Serialize};
 
 /// The output of the log-up GKR circuit.
@@ -15,6 +17,24 @@ pub struct LogUpGkrOutput<EF, B: Backend = CpuBackend> {
     pub denominator: Mle<EF, B>,
 }
 
+impl<EF: Field> LogUpGkrOutput<EF> {
+    /// The cumulative sum claimed by the output layer, `sum_i numerator_i / denominator_i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the numerator and denominator have different lengths.
+    #[must_use]
+    pub fn cumulative_sum(&self) -> EF {
+        self.numerator
+            .guts()
+            .as_slice()
+            .iter()
+            .zip_eq(self.denominator.guts().as_slice().iter())
+            .map(|(n, d)| *n / *d)
+            .sum()
+    }
+}
+
 /// The proof for a single round of the log-up GKR circuit.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LogupGkrRoundProof<EF> {
@@ -50,6 +70,17 @@ pub struct ChipEvaluation<EF> {
     pub preprocessed_trace_evaluations: Option<MleEval<EF>>,
 }
 
+impl<EF> ChipEvaluation<EF> {
+    /// Returns all the evaluations of the chip, main trace first and then preprocessed trace.
+    ///
+    /// This is the order in which the evaluations are batched by the verifier.
+    pub fn all_evaluations(&self) -> impl Iterator<Item = &EF> {
+        self.main_trace_evaluations.deref().iter().chain(
+            self.preprocessed_trace_evaluations.iter().flat_map(|evals| evals.deref().iter()),
+        )
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 /// The data passed from the GKR prover to the zerocheck prover.
 pub struct LogUpEvaluations<EF> {
@@ -58,3 +89,50 @@ pub struct LogUpEvaluations<EF> {
     /// The evaluations for each chip.
     pub chip_openings: BTreeMap<String, ChipEvaluation<EF>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+
+    use super::*;
+
+    fn gkr_output(numerator: &[u32], denominator: &[u32]) -> LogUpGkrOutput<BabyBear> {
+        let mle = |values: &[u32]| {
+            Mle::from(values.iter().copied().map(BabyBear::from_canonical_u32).collect::<Vec<_>>())
+        };
+        LogUpGkrOutput { numerator: mle(numerator), denominator: mle(denominator) }
+    }
+
+    #[test]
+    fn test_cumulative_sum() {
+        // 1/2 + 2/4 + 3/5 + 4/8 = 21/10.
+        let output = gkr_output(&[1, 2, 3, 4], &[2, 4, 5, 8]);
+        assert_eq!(
+            output.cumulative_sum(),
+            BabyBear::from_canonical_u32(21) / BabyBear::from_canonical_u32(10)
+        );
+        assert_eq!(gkr_output(&[0, 0], &[1, 1]).cumulative_sum(), BabyBear::zero());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_cumulative_sum_length_mismatch() {
+        gkr_output(&[1, 2, 3, 4], &[1, 1]).cumulative_sum();
+    }
+
+    #[test]
+    fn test_all_evaluations_order() {
+        let evaluation = ChipEvaluation {
+            main_trace_evaluations: MleEval::from(vec![1u32, 2, 3]),
+            preprocessed_trace_evaluations: Some(MleEval::from(vec![4, 5])),
+        };
+        assert_eq!(evaluation.all_evaluations().copied().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+
+        let evaluation = ChipEvaluation {
+            main_trace_evaluations: MleEval::from(vec![1u32, 2]),
+            preprocessed_trace_evaluations: None,
+        };
+        assert_eq!(evaluation.all_evaluations().copied().collect::<Vec<_>>(), vec![1, 2]);
+    }
+}
@@ -1,6 +1,9 @@
 use std::{collections::BTreeSet, marker::PhantomData, ops::Deref};
 
-use hypercube_multilinear::{full_geq, Mle, MleEval, MultilinearPcsChallenger, Point};
+use hypercube_multilinear::{
+    full_geq, try_full_lagrange_eval_ext, Mle, MleEval, MultilinearPcsChallenger, Point,
+    PointDimensionMismatch,
+};
 use hypercube_sumcheck::{partially_verify_sumcheck_proof, SumcheckError};
 use itertools::Itertools;
 use p3_challenger::FieldChallenger;
@@ -9,7 +12,7 @@ use thiserror::Error;
 
 use crate::{air::MachineAir, Chip};
 
-use super::{ChipEvaluation, LogUpEvaluations, LogUpGkrOutput, LogupGkrProof};
+use super::{ChipEvaluation, LogUpEvaluations, LogUpGkrOutput, LogupGkrProof, LogupGkrRoundProof};
 
 /// An error type for `LogUp` GKR.
 #[derive(Debug, Error)]
@@ -44,6 +47,9 @@ pub enum LogupGkrVerificationError<EF> {
     /// The denominator evaluation does not match the expected one.
     #[error("denominator evaluation mismatch: {0} != {1}")]
     DenominatorEvaluationMismatch(EF, EF),
+    /// The sumcheck point and the evaluation point of a round have different dimensions.
+    #[error("round point dimension mismatch: {0}")]
+    PointDimensionMismatch(#[from] PointDimensionMismatch),
 }
 
 /// Verifier for `LogUp` GKR.
@@ -56,6 +62,79 @@ where
     EF: ExtensionField<F>,
     A: MachineAir<F>,
 {
+    /// The number of variables needed to index `num_of_interactions` interactions.
+    ///
+    /// The interaction dimension is padded to a power of two. Shards with zero or one interactions
+    /// both use zero interaction variables, i.e. a single slot. With no interactions that slot only
+    /// holds padding (numerator zero, denominator one). Chips that neither send nor receive take no
+    /// slots, but their openings are still observed.
+    #[must_use]
+    pub fn number_of_interaction_variables(num_of_interactions: usize) -> u32 {
+        match num_of_interactions {
+            0 | 1 => 0,
+            n => n.next_power_of_two().ilog2(),
+        }
+    }
+
+    /// Verify a single round of the `LogUp` GKR protocol.
+    ///
+    /// Checks `round_proof` against the claimed `numerator_eval` and `denominator_eval` of the
+    /// current layer at `eval_point`, batched with `lambda`. Returns the claimed numerator and
+    /// denominator evaluations of the next layer and the point they are claimed at. `round` is
+    /// only used to label errors.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sumcheck proof of the round is invalid or inconsistent with the
+    /// claims.
+    pub fn verify_gkr_round(
+        round: usize,
+        round_proof: &LogupGkrRoundProof<EF>,
+        numerator_eval: EF,
+        denominator_eval: EF,
+        eval_point: &Point<EF>,
+        lambda: EF,
+        challenger: &mut impl FieldChallenger<F>,
+    ) -> Result<(EF, EF, Point<EF>), LogupGkrVerificationError<EF>> {
+        // Check that the claimed sum is consistent with the previous round values.
+        let expected_claim = numerator_eval * lambda + denominator_eval;
+        if round_proof.sumcheck_proof.claimed_sum != expected_claim {
+            return Err(LogupGkrVerificationError::InconsistentSumcheckClaim(round));
+        }
+        // Verify the sumcheck proof.
+        partially_verify_sumcheck_proof(&round_proof.sumcheck_proof, challenger)?;
+        // Verify that the evaluation claim is consistent with the prover messages.
+        let (point, final_eval) = round_proof.sumcheck_proof.point_and_eval.clone();
+        let eq_eval = try_full_lagrange_eval_ext(&point, eval_point)?;
+        let numerator_sumcheck_eval = round_proof.numerator_0 * round_proof.denominator_1
+            + round_proof.numerator_1 * round_proof.denominator_0;
+        let denominator_sumcheck_eval = round_proof.denominator_0 * round_proof.denominator_1;
+        let expected_final_eval =
+            eq_eval * (numerator_sumcheck_eval * lambda + denominator_sumcheck_eval);
+        if final_eval != expected_final_eval {
+            return Err(LogupGkrVerificationError::InconsistentEvaluation(round));
+        }
+
+        // Observe the prover message.
+        challenger.observe_ext_element(round_proof.numerator_0);
+        challenger.observe_ext_element(round_proof.numerator_1);
+        challenger.observe_ext_element(round_proof.denominator_0);
+        challenger.observe_ext_element(round_proof.denominator_1);
+
+        // Get the evaluation point for the claims of the next round.
+        let mut eval_point = point;
+        // Sample the last coordinate and add to the point.
+        let last_coordinate = challenger.sample_ext_element::<EF>();
+        eval_point.add_dimension_back(last_coordinate);
+        // Update the evaluation of the numerator and denominator at the last coordinate.
+        let numerator_eval = round_proof.numerator_0
+            + (round_proof.numerator_1 - round_proof.numerator_0) * last_coordinate;
+        let denominator_eval = round_proof.denominator_0
+            + (round_proof.denominator_1 - round_proof.denominator_0) * last_coordinate;
+
+        Ok((numerator_eval, denominator_eval, eval_point))
+    }
+
     /// Verify the `LogUp` GKR proof.
     ///
     /// # Errors
@@ -86,13 +165,7 @@ where
         }
 
         // Verify that the cumulative sum matches the claimed one.
-        let output_cumulative_sum = numerator
-            .guts()
-            .as_slice()
-            .iter()
-            .zip_eq(denominator.guts().as_slice().iter())
-            .map(|(n, d)| *n / *d)
-            .sum::<EF>();
+        let output_cumulative_sum = circuit_output.cumulative_sum();
         if output_cumulative_sum != cumulative_sum {
             return Err(LogupGkrVerificationError::CumulativeSumMismatch(
                 output_cumulative_sum,
@@ -103,7 +176,8 @@ where
         // Calculate the interaction number.
         let num_of_interactions =
             shard_chips.iter().map(|c| c.sends().len() + c.receives().len()).sum::<usize>();
-        let number_of_interaction_variables = num_of_interactions.next_power_of_two().ilog2();
+        let number_of_interaction_variables =
+            Self::number_of_interaction_variables(num_of_interactions);
         // Assert that the size of the first layer matches the expected one.
         let initial_number_of_variables = numerator.num_variables();
         if initial_number_of_variables != number_of_interaction_variables + 1 {
@@ -122,41 +196,15 @@ where
         for (i, round_proof) in round_proofs.iter().enumerate() {
             // Get the batching challenge for combining the claims.
             let lambda = challenger.sample_ext_element::<EF>();
-            // Check that the claimed sum is consistent with the previous round values.
-            let expected_claim = numerator_eval * lambda + denominator_eval;
-            if round_proof.sumcheck_proof.claimed_sum != expected_claim {
-                return Err(LogupGkrVerificationError::InconsistentSumcheckClaim(i));
-            }
-            // Verify the sumcheck proof.
-            partially_verify_sumcheck_proof(&round_proof.sumcheck_proof, challenger)?;
-            // Verify that the evaluation claim is consistent with the prover messages.
-            let (point, final_eval) = round_proof.sumcheck_proof.point_and_eval.clone();
-            let eq_eval = Mle::full_lagrange_eval(&point, &eval_point);
-            let numerator_sumcheck_eval = round_proof.numerator_0 * round_proof.denominator_1
-                + round_proof.numerator_1 * round_proof.denominator_0;
-            let denominator_sumcheck_eval = round_proof.denominator_0 * round_proof.denominator_1;
-            let expected_final_eval =
-                eq_eval * (numerator_sumcheck_eval * lambda + denominator_sumcheck_eval);
-            if final_eval != expected_final_eval {
-                return Err(LogupGkrVerificationError::InconsistentEvaluation(i));
-            }
-
-            // Observe the prover message.
-            challenger.observe_ext_element(round_proof.numerator_0);
-            challenger.observe_ext_element(round_proof.numerator_1);
-            challenger.observe_ext_element(round_proof.denominator_0);
-            challenger.observe_ext_element(round_proof.denominator_1);
-
-            // Get the evaluation point for the claims of the next round.
-            eval_point = round_proof.sumcheck_proof.point_and_eval.0.clone();
-            // Sample the last coordinate and add to the point.
-            let last_coordinate = challenger.sample_ext_element::<EF>();
-            eval_point.add_dimension_back(last_coordinate);
-            // Update the evaluation of the numerator and denominator at the last coordinate.
-            numerator_eval = round_proof.numerator_0
-                + (round_proof.numerator_1 - round_proof.numerator_0) * last_coordinate;
-            denominator_eval = round_proof.denominator_0
-                + (round_proof.denominator_1 - round_proof.denominator_0) * last_coordinate;
+            (numerator_eval, denominator_eval, eval_point) = Self::verify_gkr_round(
+                i,
+                round_proof,
+                numerator_eval,
+                denominator_eval,
+                &eval_point,
+                lambda,
+                challenger,
+            )?;
         }
 
         // Verify that the last layer evaluations are consistent with the evaluations of the traces.
@@ -178,9 +226,11 @@ where
         }
 
         // Compute the expected opening of the last layer numerator and denominator values from the
-        // trace openings.
-        let mut numerator_values = Vec::with_capacity(num_of_interactions);
-        let mut denominator_values = Vec::with_capacity(num_of_interactions);
+        // trace openings. The values are padded to the size of the interaction hypercube, so we
+        // allocate the final size up front.
+        let num_padded_interactions = 1 << interaction_point.dimension();
+        let mut numerator_values = Vec::with_capacity(num_padded_interactions);
+        let mut denominator_values = Vec::with_capacity(num_padded_interactions);
         let mut point_extended = point.clone();
         point_extended.add_dimension(EF::zero());
         for ((chip, openings), threshold) in
@@ -233,10 +283,10 @@ where
         }
         // Convert the values to a multilinear polynomials.
         // Pad the numerator values with zeros.
-        numerator_values.resize(1 << interaction_point.dimension(), EF::zero());
+        numerator_values.resize(num_padded_interactions, EF::zero());
         let numerator = Mle::from(numerator_values);
         // Pad the denominator values with ones.
-        denominator_values.resize(1 << interaction_point.dimension(), EF::one());
+        denominator_values.resize(num_padded_interactions, EF::one());
         let denominator = Mle::from(denominator_values);
 
         let expected_numerator_eval = numerator.blocking_eval_at(&interaction_point)[0];
@@ -256,3 +306,301 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use hypercube_algebra::interpolate_univariate_polynomial;
+    use hypercube_basefold::{BasefoldVerifier, Poseidon2BabyBear16BasefoldConfig};
+    use hypercube_merkle_tree::{my_bb_16_perm, Perm};
+    use hypercube_sumcheck::PartialSumcheckProof;
+    use p3_air::{Air, AirBuilder, BaseAir};
+    use p3_baby_bear::BabyBear;
+    use p3_challenger::{CanObserve, DuplexChallenger};
+    use p3_field::{extension::BinomialExtensionField, AbstractExtensionField, AbstractField};
+    use p3_matrix::Matrix;
+
+    use crate::{
+        air::{AirInteraction, InteractionScope, MessageBuilder},
+        InteractionKind,
+    };
+
+    use super::*;
+
+    /// A toy chip with one column, sending it once if `sends` is set.
+    struct ToyAir {
+        sends: bool,
+    }
+
+    impl<F: Field> BaseAir<F> for ToyAir {
+        fn width(&self) -> usize {
+            1
+        }
+    }
+
+    impl<F: Field> MachineAir<F> for ToyAir {
+        fn name(&self) -> String {
+            let name = if self.sends { "Sender" } else { "Silent" };
+            name.to_string()
+        }
+    }
+
+    impl<AB: AirBuilder + MessageBuilder<AirInteraction<AB::Expr>>> Air<AB> for ToyAir {
+        fn eval(&self, builder: &mut AB) {
+            let main = builder.main();
+            let local = main.row_slice(0)[0];
+            if self.sends {
+                builder.send(
+                    AirInteraction::new(
+                        vec![local.into()],
+                        AB::Expr::one(),
+                        InteractionKind::Memory,
+                    ),
+                    InteractionScope::Local,
+                );
+            }
+        }
+    }
+
+    type EF = BinomialExtensionField<BabyBear, 4>;
+
+    type Verifier = LogUpGkrVerifier<BabyBear, EF, ToyAir>;
+
+    type Challenger = DuplexChallenger<BabyBear, Perm, 16, 8>;
+
+    #[test]
+    fn test_number_of_interaction_variables() {
+        for (num_of_interactions, expected) in
+            [(0, 0), (1, 0), (2, 1), (3, 2), (4, 2), (5, 3), (8, 3), (9, 4), (1 << 10, 10)]
+        {
+            assert_eq!(
+                Verifier::number_of_interaction_variables(num_of_interactions),
+                expected,
+                "{num_of_interactions} interactions"
+            );
+        }
+    }
+
+    #[test]
+    fn test_silent_chip_takes_no_interaction_slots() {
+        let silent = Chip::<BabyBear, _>::new(ToyAir { sends: false });
+        let sender = Chip::<BabyBear, _>::new(ToyAir { sends: true });
+        assert_eq!(silent.num_interactions(), 0);
+        assert_eq!(sender.num_interactions(), 1);
+
+        let count = |chips: &[&Chip<BabyBear, ToyAir>]| {
+            chips.iter().map(|c| c.sends().len() + c.receives().len()).sum::<usize>()
+        };
+        assert_eq!(Verifier::number_of_interaction_variables(count(&[&silent])), 0);
+        assert_eq!(Verifier::number_of_interaction_variables(count(&[&silent, &sender])), 0);
+        assert_eq!(
+            Verifier::number_of_interaction_variables(count(&[&silent, &sender, &sender])),
+            1
+        );
+    }
+
+    #[test]
+    fn test_verify_logup_gkr_silent_chip() {
+        let silent = Chip::<BabyBear, _>::new(ToyAir { sends: false });
+        let shard_chips = BTreeSet::from([silent]);
+        // A single row of padding for a chip with one row, over a trace with one variable.
+        let degrees = [Point::<BabyBear>::from_usize(1, 2)];
+        let challenger = BasefoldVerifier::<Poseidon2BabyBear16BasefoldConfig>::new(1).challenger();
+
+        // With no interactions the output layer only holds padding, and there are no GKR rounds.
+        let proof_with_numerator = |numerator: Vec<EF>| {
+            let circuit_output = LogUpGkrOutput {
+                numerator: Mle::from(numerator),
+                denominator: Mle::from(vec![EF::one(); 2]),
+            };
+            let mut challenger = challenger.clone();
+            for (n, d) in circuit_output
+                .numerator
+                .guts()
+                .as_slice()
+                .iter()
+                .zip(circuit_output.denominator.guts().as_slice())
+            {
+                challenger.observe_ext_element(*n);
+                challenger.observe_ext_element(*d);
+            }
+            let point = challenger.sample_point::<EF>(1);
+            let chip_openings = BTreeMap::from([(
+                "Silent".to_string(),
+                ChipEvaluation {
+                    main_trace_evaluations: MleEval::from(vec![EF::two()]),
+                    preprocessed_trace_evaluations: None,
+                },
+            )]);
+            LogupGkrProof {
+                circuit_output,
+                round_proofs: vec![],
+                logup_evaluations: LogUpEvaluations { point, chip_openings },
+            }
+        };
+        let verify = |cumulative_sum: EF, proof: &LogupGkrProof<EF>| {
+            Verifier::verify_logup_gkr(
+                &shard_chips,
+                &degrees,
+                EF::two(),
+                EF::from_canonical_u32(3),
+                cumulative_sum,
+                1,
+                proof,
+                &mut challenger.clone(),
+            )
+        };
+
+        let proof = proof_with_numerator(vec![EF::zero(); 2]);
+        verify(EF::zero(), &proof).unwrap();
+        assert!(matches!(
+            verify(EF::one(), &proof),
+            Err(LogupGkrVerificationError::CumulativeSumMismatch(..))
+        ));
+
+        // A non-zero output cannot come from a shard without interactions.
+        let proof = proof_with_numerator(vec![EF::one(), EF::zero()]);
+        assert!(matches!(
+            verify(EF::one(), &proof),
+            Err(LogupGkrVerificationError::NumeratorEvaluationMismatch(..))
+        ));
+    }
+
+    /// The claims at `eval_point` of a layer whose one-variable children have the given numerator
+    /// and denominator values, and an honest proof of the round reducing them, produced with a
+    /// clone of `challenger`.
+    fn honest_round(
+        [numerator_0, numerator_1, denominator_0, denominator_1]: [[u32; 2]; 4],
+        eval_point: EF,
+        lambda: EF,
+        challenger: &Challenger,
+    ) -> (EF, EF, LogupGkrRoundProof<EF>) {
+        let line = |[v_0, v_1]: [u32; 2], x: EF| {
+            let v_0 = EF::from_canonical_u32(v_0);
+            v_0 + (EF::from_canonical_u32(v_1) - v_0) * x
+        };
+        let numerator = |x| {
+            line(numerator_0, x) * line(denominator_1, x)
+                + line(numerator_1, x) * line(denominator_0, x)
+        };
+        let denominator = |x| line(denominator_0, x) * line(denominator_1, x);
+        let eq = |x: EF| x * eval_point + (EF::one() - x) * (EF::one() - eval_point);
+
+        let numerator_eval =
+            eq(EF::zero()) * numerator(EF::zero()) + eq(EF::one()) * numerator(EF::one());
+        let denominator_eval =
+            eq(EF::zero()) * denominator(EF::zero()) + eq(EF::one()) * denominator(EF::one());
+
+        let xs = (0..4).map(EF::from_canonical_u32).collect::<Vec<_>>();
+        let ys = xs
+            .iter()
+            .map(|&x| eq(x) * (numerator(x) * lambda + denominator(x)))
+            .collect::<Vec<_>>();
+        let poly = interpolate_univariate_polynomial(&xs, &ys);
+
+        let mut challenger = challenger.clone();
+        challenger.observe_slice(
+            &poly.coefficients.iter().flat_map(|x| x.as_base_slice()).copied().collect::<Vec<_>>(),
+        );
+        let alpha: EF = challenger.sample_ext_element();
+        let round_proof = LogupGkrRoundProof {
+            numerator_0: line(numerator_0, alpha),
+            numerator_1: line(numerator_1, alpha),
+            denominator_0: line(denominator_0, alpha),
+            denominator_1: line(denominator_1, alpha),
+            sumcheck_proof: PartialSumcheckProof {
+                claimed_sum: numerator_eval * lambda + denominator_eval,
+                point_and_eval: ([alpha].into_iter().collect(), poly.eval_at_point(alpha)),
+                univariate_polys: vec![poly],
+            },
+        };
+        (numerator_eval, denominator_eval, round_proof)
+    }
+
+    #[test]
+    fn test_verify_gkr_round() {
+        let values = [[1, 2], [3, 4], [5, 6], [7, 8]];
+        let (eval_point, lambda) = (EF::from_canonical_u32(11), EF::from_canonical_u32(13));
+        let challenger = Challenger::new(my_bb_16_perm());
+        let (numerator_eval, denominator_eval, round_proof) =
+            honest_round(values, eval_point, lambda, &challenger);
+
+        let mut verifier_challenger = challenger.clone();
+        let (next_numerator, next_denominator, next_point) = Verifier::verify_gkr_round(
+            0,
+            &round_proof,
+            numerator_eval,
+            denominator_eval,
+            &[eval_point].into_iter().collect(),
+            lambda,
+            &mut verifier_challenger,
+        )
+        .unwrap();
+
+        // Replay the transcript to get the last coordinate of the next point.
+        let mut challenger = challenger;
+        challenger.observe_slice(
+            &round_proof.sumcheck_proof.univariate_polys[0]
+                .coefficients
+                .iter()
+                .flat_map(|x| x.as_base_slice())
+                .copied()
+                .collect::<Vec<_>>(),
+        );
+        let alpha: EF = challenger.sample_ext_element();
+        for value in [
+            round_proof.numerator_0,
+            round_proof.numerator_1,
+            round_proof.denominator_0,
+            round_proof.denominator_1,
+        ] {
+            challenger.observe_ext_element(value);
+        }
+        let last_coordinate: EF = challenger.sample_ext_element();
+
+        assert_eq!(next_point, [alpha, last_coordinate].into_iter().collect::<Point<EF>>());
+        assert_eq!(
+            next_numerator,
+            round_proof.numerator_0
+                + (round_proof.numerator_1 - round_proof.numerator_0) * last_coordinate
+        );
+        assert_eq!(
+            next_denominator,
+            round_proof.denominator_0
+                + (round_proof.denominator_1 - round_proof.denominator_0) * last_coordinate
+        );
+    }
+
+    #[test]
+    fn test_verify_gkr_round_inconsistent() {
+        let values = [[1, 2], [3, 4], [5, 6], [7, 8]];
+        let (eval_point, lambda) = (EF::from_canonical_u32(11), EF::from_canonical_u32(13));
+        let challenger = Challenger::new(my_bb_16_perm());
+        let (numerator_eval, denominator_eval, round_proof) =
+            honest_round(values, eval_point, lambda, &challenger);
+        let verify = |numerator_eval: EF, round_proof: &LogupGkrRoundProof<EF>| {
+            Verifier::verify_gkr_round(
+                3,
+                round_proof,
+                numerator_eval,
+                denominator_eval,
+                &[eval_point].into_iter().collect(),
+                lambda,
+                &mut challenger.clone(),
+            )
+        };
+
+        assert!(matches!(
+            verify(numerator_eval + EF::one(), &round_proof),
+            Err(LogupGkrVerificationError::InconsistentSumcheckClaim(3))
+        ));
+
+        let mut tampered = round_proof;
+        tampered.numerator_0 += EF::one();
+        assert!(matches!(
+            verify(numerator_eval, &tampered),
+            Err(LogupGkrVerificationError::InconsistentEvaluation(3))
+        ));
+    }
+}
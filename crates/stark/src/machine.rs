@@ -1,8 +1,14 @@
 use derive_where::derive_where;
+use itertools::Itertools;
+use p3_air::BaseAir;
 use p3_field::Field;
 use std::collections::BTreeSet;
+use thiserror::Error;
 
-use crate::{air::MachineAir, Chip};
+use crate::{
+    air::{InteractionScope, MachineAir},
+    Chip,
+};
 
 /// A shape for a machine.
 #[derive(Debug)]
@@ -45,6 +51,28 @@ pub struct Machine<F: Field, A> {
     num_pv_elts: usize,
 }
 
+/// The error returned when a machine's public values count does not match the expected layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("machine declares {actual} public values elements, expected {expected}")]
+pub struct PvLayoutError {
+    /// The number of public values elements of the expected layout.
+    pub expected: usize,
+    /// The number of public values elements declared by the machine.
+    pub actual: usize,
+}
+
+/// The error returned when a list of chip names is not a canonically ordered subset of a machine's
+/// chips.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ChipOrderError {
+    /// The machine has no chip with this name.
+    #[error("unknown chip: {0}")]
+    UnknownChip(String),
+    /// The chip at this index does not come strictly after the previous one in canonical order.
+    #[error("chip {1} at index {0} is out of order")]
+    OutOfOrder(usize, String),
+}
+
 impl<F, A> Machine<F, A>
 where
     F: Field,
@@ -67,4 +95,250 @@ where
     pub const fn num_pv_elts(&self) -> usize {
         self.num_pv_elts
     }
+
+    /// Returns whether any chip in the machine has a preprocessed trace.
+    #[must_use]
+    pub fn has_preprocessed(&self) -> bool {
+        self.chips.iter().any(Chip::has_preprocessed)
+    }
+
+    /// Returns whether any chip in the machine has constraints on the public values.
+    #[must_use]
+    pub fn has_public_constraints(&self) -> bool {
+        self.chips.iter().any(|chip| chip.air.has_public_constraints())
+    }
+
+    /// Checks that the machine's public values count matches the size of the public values struct
+    /// it is meant to be used with.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PvLayoutError`] if the counts differ.
+    pub fn validate_pv_layout(&self, expected_num_pv_elts: usize) -> Result<(), PvLayoutError> {
+        if self.num_pv_elts == expected_num_pv_elts {
+            Ok(())
+        } else {
+            Err(PvLayoutError { expected: expected_num_pv_elts, actual: self.num_pv_elts })
+        }
+    }
+
+    /// Returns `(chip_name, preprocessed_width, main_width)` for every chip, in machine order.
+    ///
+    /// This is a serializable summary for checking proof openings without the machine itself.
+    #[must_use]
+    pub fn shape_descriptor(&self) -> Vec<(String, usize, usize)> {
+        self.chips
+            .iter()
+            .map(|chip| (chip.name(), chip.preprocessed_width(), chip.width()))
+            .collect()
+    }
+
+    /// Returns a blake3 hash of the machine's chip set, identifying it independently of chip order.
+    ///
+    /// The hash covers every chip's name, preprocessed width and main width, sorted by name, so
+    /// machines whose chips merely share names hash differently.
+    #[must_use]
+    pub fn chip_set_hash(&self) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        for (name, preprocessed_width, width) in
+            self.shape_descriptor().into_iter().sorted_by(|a, b| a.0.cmp(&b.0))
+        {
+            hasher.update(&(name.len() as u64).to_le_bytes());
+            hasher.update(name.as_bytes());
+            hasher.update(&(preprocessed_width as u64).to_le_bytes());
+            hasher.update(&(width as u64).to_le_bytes());
+        }
+        *hasher.finalize().as_bytes()
+    }
+
+    /// Checks that `names` are names of chips of the machine, listed without repetition in the
+    /// canonical order, i.e. the order of a [`BTreeSet`] of chips.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ChipOrderError`] describing the first offending name.
+    pub fn verify_chip_ordering(&self, names: &[String]) -> Result<(), ChipOrderError> {
+        let chip_names = self.chips.iter().map(Chip::name).collect::<BTreeSet<_>>();
+        for (i, name) in names.iter().enumerate() {
+            if !chip_names.contains(name) {
+                return Err(ChipOrderError::UnknownChip(name.clone()));
+            }
+            if i > 0 && names[i - 1] >= *name {
+                return Err(ChipOrderError::OutOfOrder(i, name.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the total number of interactions in the given scope across all chips.
+    #[must_use]
+    pub fn total_interactions_in_scope(&self, scope: InteractionScope) -> usize {
+        self.chips.iter().map(|chip| chip.num_interactions_in_scope(scope)).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_air::{Air, AirBuilder};
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use p3_matrix::Matrix;
+
+    use crate::{
+        air::{AirInteraction, MessageBuilder},
+        InteractionKind,
+    };
+
+    use super::*;
+
+    /// A toy chip sending its column in the local scope, and sending and receiving it in the
+    /// global scope.
+    struct MixedScopeAir;
+
+    impl<F: Field> BaseAir<F> for MixedScopeAir {
+        fn width(&self) -> usize {
+            1
+        }
+    }
+
+    impl<F: Field> MachineAir<F> for MixedScopeAir {
+        fn name(&self) -> String {
+            "MixedScope".to_string()
+        }
+    }
+
+    impl<AB: AirBuilder + MessageBuilder<AirInteraction<AB::Expr>>> Air<AB> for MixedScopeAir {
+        fn eval(&self, builder: &mut AB) {
+            let main = builder.main();
+            let local = main.row_slice(0)[0];
+            let message = || {
+                AirInteraction::new(vec![local.into()], AB::Expr::one(), InteractionKind::Memory)
+            };
+            builder.send(message(), InteractionScope::Local);
+            builder.send(message(), InteractionScope::Global);
+            builder.receive(message(), InteractionScope::Global);
+        }
+    }
+
+    #[test]
+    fn test_interactions_in_scope() {
+        let chip = Chip::<BabyBear, _>::new(MixedScopeAir);
+        assert_eq!(chip.num_interactions_in_scope(InteractionScope::Local), 1);
+        assert_eq!(chip.num_interactions_in_scope(InteractionScope::Global), 2);
+
+        let machine = Machine::new(vec![chip.clone(), chip], 0);
+        assert_eq!(machine.total_interactions_in_scope(InteractionScope::Local), 2);
+        assert_eq!(machine.total_interactions_in_scope(InteractionScope::Global), 4);
+    }
+
+    #[test]
+    fn test_validate_pv_layout() {
+        let machine = Machine::new(vec![Chip::<BabyBear, _>::new(MixedScopeAir)], 4);
+        assert_eq!(machine.validate_pv_layout(4), Ok(()));
+        assert_eq!(machine.validate_pv_layout(5), Err(PvLayoutError { expected: 5, actual: 4 }));
+    }
+
+    /// A toy chip with a preprocessed trace of the given width, sending its main column.
+    struct PreprocessedAir {
+        name: &'static str,
+        preprocessed_width: usize,
+    }
+
+    impl<F: Field> BaseAir<F> for PreprocessedAir {
+        fn width(&self) -> usize {
+            1
+        }
+    }
+
+    impl<F: Field> MachineAir<F> for PreprocessedAir {
+        fn name(&self) -> String {
+            self.name.to_string()
+        }
+
+        fn preprocessed_width(&self) -> usize {
+            self.preprocessed_width
+        }
+    }
+
+    impl<AB: AirBuilder + MessageBuilder<AirInteraction<AB::Expr>>> Air<AB> for PreprocessedAir {
+        fn eval(&self, builder: &mut AB) {
+            let main = builder.main();
+            let local = main.row_slice(0)[0];
+            builder.send(
+                AirInteraction::new(vec![local.into()], AB::Expr::one(), InteractionKind::Memory),
+                InteractionScope::Local,
+            );
+        }
+    }
+
+    #[test]
+    fn test_has_preprocessed() {
+        let chips = [("A", 0), ("B", 2), ("C", 0), ("D", 1)]
+            .into_iter()
+            .map(|(name, preprocessed_width)| {
+                Chip::<BabyBear, _>::new(PreprocessedAir { name, preprocessed_width })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            chips.iter().map(Chip::has_preprocessed).collect::<Vec<_>>(),
+            [false, true, false, true]
+        );
+        // The chips with a preprocessed trace keep their relative order.
+        assert_eq!(
+            chips.iter().filter(|chip| chip.has_preprocessed()).map(Chip::name).collect::<Vec<_>>(),
+            ["B", "D"]
+        );
+
+        assert!(Machine::new(chips.clone(), 0).has_preprocessed());
+        let chips = chips.into_iter().filter(|chip| !chip.has_preprocessed()).collect();
+        assert!(!Machine::new(chips, 0).has_preprocessed());
+    }
+
+    #[test]
+    fn test_verify_chip_ordering() {
+        let chips = ["Cpu", "Add", "Memory"]
+            .into_iter()
+            .map(|name| Chip::<BabyBear, _>::new(PreprocessedAir { name, preprocessed_width: 0 }))
+            .collect();
+        let machine = Machine::new(chips, 0);
+        let names = |names: &[&str]| names.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        assert_eq!(machine.verify_chip_ordering(&names(&["Add", "Cpu", "Memory"])), Ok(()));
+        assert_eq!(machine.verify_chip_ordering(&names(&["Add", "Memory"])), Ok(()));
+        assert_eq!(machine.verify_chip_ordering(&[]), Ok(()));
+        assert_eq!(
+            machine.verify_chip_ordering(&names(&["Add", "Memory", "Cpu"])),
+            Err(ChipOrderError::OutOfOrder(2, "Cpu".to_string()))
+        );
+        assert_eq!(
+            machine.verify_chip_ordering(&names(&["Add", "Add"])),
+            Err(ChipOrderError::OutOfOrder(1, "Add".to_string()))
+        );
+        assert_eq!(
+            machine.verify_chip_ordering(&names(&["Add", "Mul"])),
+            Err(ChipOrderError::UnknownChip("Mul".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_chip_set_hash() {
+        let machine = |chips: &[(&'static str, usize)]| {
+            let chips = chips
+                .iter()
+                .map(|&(name, preprocessed_width)| {
+                    Chip::<BabyBear, _>::new(PreprocessedAir { name, preprocessed_width })
+                })
+                .collect();
+            Machine::new(chips, 0)
+        };
+
+        let hash = machine(&[("A", 0), ("B", 1)]).chip_set_hash();
+        // The hash does not depend on the order of the chips.
+        assert_eq!(machine(&[("B", 1), ("A", 0)]).chip_set_hash(), hash);
+
+        assert_ne!(machine(&[("A", 0), ("C", 1)]).chip_set_hash(), hash);
+        assert_ne!(machine(&[("A", 0)]).chip_set_hash(), hash);
+        // Same names but a different width.
+        assert_ne!(machine(&[("A", 0), ("B", 2)]).chip_set_hash(), hash);
+    }
 }
@@ -46,6 +46,12 @@ impl<F: Field> SepticCurve<F> {
         self.y.square() == Self::curve_formula(self.x)
     }
 
+    /// Checks that the point satisfies the curve equation `y^2 = x^3 + 2x + 26z^5`, same as
+    /// [Self::check_on_point].
+    pub fn is_on_curve(&self) -> bool {
+        self.check_on_point()
+    }
+
     /// Negates a `SepticCurve` point.
     #[must_use]
     pub fn neg(&self) -> Self {
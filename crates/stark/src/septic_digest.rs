@@ -3,6 +3,7 @@ use crate::{septic_curve::SepticCurve, septic_extension::SepticExtension};
 use p3_field::{AbstractExtensionField, AbstractField, Field};
 use serde::{Deserialize, Serialize};
 use std::{iter::Sum, ops::Add};
+use thiserror::Error;
 
 /// The x-coordinate for a curve point used as a starting cumulative sum for global permutation
 /// trace generation, derived from `sqrt(2)`.
@@ -56,11 +57,29 @@ impl<F: AbstractField> SepticDigest<F> {
     }
 }
 
+/// The error returned when a digest is not a point on the septic curve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("septic digest is not on the curve")]
+pub struct NotOnCurve;
+
 impl<F: Field> SepticDigest<F> {
     /// Checks that the digest is zero, the starting point of the accumulation.
     pub fn is_zero(&self) -> bool {
         *self == SepticDigest::<F>::zero()
     }
+
+    /// Checks that the digest is a point on the curve.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NotOnCurve`] if the point does not satisfy the curve equation.
+    pub fn validate(&self) -> Result<(), NotOnCurve> {
+        if self.0.is_on_curve() {
+            Ok(())
+        } else {
+            Err(NotOnCurve)
+        }
+    }
 }
 
 impl<F: Field> Add for SepticDigest<F> {
@@ -128,4 +147,16 @@ mod test {
         let point = SepticCurve { x, y };
         assert!(point.check_on_point());
     }
+
+    #[test]
+    fn test_validate() {
+        let digest = SepticDigest::<BabyBear>::zero();
+        assert!(digest.validate().is_ok());
+        assert!(SepticDigest::<BabyBear>::starting_digest().validate().is_ok());
+
+        let mut tampered = digest;
+        tampered.0.y.0[0] += BabyBear::one();
+        assert!(!tampered.0.is_on_curve());
+        assert_eq!(tampered.validate(), Err(NotOnCurve));
+    }
 }
@@ -669,6 +669,16 @@ impl<F: Field> SepticExtension<F> {
 
         Some(denominator * x.real)
     }
+
+    /// Returns `a` if `cond` is true and `b` otherwise, without branching on `cond`.
+    ///
+    /// Each limb is computed as `b + cond * (a - b)`, mirroring the select operations of the
+    /// recursion circuit.
+    #[must_use]
+    pub fn select(cond: bool, a: &Self, b: &Self) -> Self {
+        let mask = F::from_bool(cond);
+        SepticExtension(core::array::from_fn(|i| b.0[i] + mask * (a.0[i] - b.0[i])))
+    }
 }
 
 impl<F: PrimeField32> SepticExtension<F> {
@@ -821,3 +831,20 @@ impl<T> IntoIterator for SepticBlock<T> {
         self.0.into_iter()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use p3_baby_bear::BabyBear;
+
+    #[test]
+    fn test_select() {
+        let a = SepticExtension::<BabyBear>::from_base_fn(BabyBear::from_canonical_usize);
+        let b =
+            SepticExtension::<BabyBear>::from_base_fn(|i| BabyBear::from_canonical_usize(100 + i));
+        assert_eq!(SepticExtension::select(true, &a, &b), a);
+        assert_eq!(SepticExtension::select(false, &a, &b), b);
+        assert_eq!(SepticExtension::select(true, &a, &a), a);
+        assert_eq!(SepticExtension::select(false, &a, &a), a);
+    }
+}
@@ -27,6 +27,15 @@ pub fn batch_multiplicative_inverse_inplace<F: Field>(values: &mut [F]) {
     }
 }
 
+/// Decodes a trace height from its big-endian bits, as encoded in a chip's opened `degree`.
+///
+/// This is the value the verifier observes for each chip, so every verifier must decode heights
+/// through this function.
+#[must_use]
+pub fn decode_height<F: Field>(degree: &[F]) -> F {
+    degree.iter().fold(F::zero(), |acc, &x| x + F::two() * acc)
+}
+
 /// Compute the ceiling of the base-2 logarithm of a number.
 #[must_use]
 pub fn log2_ceil_usize(n: usize) -> usize {
@@ -38,3 +47,24 @@ pub fn log2_ceil_usize(n: usize) -> usize {
 pub fn inner_perm() -> hypercube_merkle_tree::Perm {
     my_bb_16_perm()
 }
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+
+    use super::*;
+
+    #[test]
+    fn test_decode_height() {
+        let degree = |bits: &[u32]| {
+            bits.iter().copied().map(BabyBear::from_canonical_u32).collect::<Vec<_>>()
+        };
+        assert_eq!(decode_height::<BabyBear>(&[]), BabyBear::zero());
+        assert_eq!(decode_height(&degree(&[0, 0, 0])), BabyBear::zero());
+        assert_eq!(decode_height(&degree(&[1])), BabyBear::one());
+        assert_eq!(decode_height(&degree(&[1, 0, 0, 0])), BabyBear::from_canonical_u32(8));
+        assert_eq!(decode_height(&degree(&[0, 1, 1, 0, 1])), BabyBear::from_canonical_u32(13));
+        assert_eq!(decode_height(&degree(&[1; 5])), BabyBear::from_canonical_u32(31));
+    }
+}
@@ -62,7 +62,7 @@ where
 
     /// Hash the verifying key, an array of `BabyBear` elements.
     pub fn hash_babybear(&self) -> [BabyBear; DIGEST_SIZE] {
-        let num_inputs = DIGEST_SIZE + 1 + 14 + (4 * self.preprocessed_chip_information.len());
+        let num_inputs = C::digest_size() + 1 + 14 + (4 * self.preprocessed_chip_information.len());
         let mut inputs = Vec::with_capacity(num_inputs);
         inputs.extend(
             self.preprocessed_commit
@@ -3,10 +3,12 @@ use std::collections::BTreeSet;
 use hypercube_jagged::JaggedPcsProof;
 use hypercube_multilinear::Point;
 use hypercube_sumcheck::PartialSumcheckProof;
+use p3_field::{Field, PrimeField32};
 use p3_matrix::{dense::RowMajorMatrixView, stack::VerticalPair};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-use crate::LogupGkrProof;
+use crate::{decode_height, log2_ceil_usize, LogupGkrProof};
 
 use super::MachineConfig;
 
@@ -52,6 +54,84 @@ pub struct ShardProof<C: MachineConfig> {
     pub shard_chips: BTreeSet<String>,
 }
 
+#[cfg(feature = "proof-hash")]
+impl<C: MachineConfig> ShardProof<C>
+where
+    C::Challenger: Serialize,
+{
+    /// A blake3 hash of the bincode serialization of the proof.
+    ///
+    /// The hash is deterministic, so it can key a cache of verification results. It is not part of
+    /// the protocol and is never observed by the verifier.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the proof fails to serialize.
+    #[must_use]
+    pub fn content_hash(&self) -> [u8; 32] {
+        let bytes = bincode::serialize(self).expect("failed to serialize shard proof");
+        *blake3::hash(&bytes).as_bytes()
+    }
+}
+
+/// The chip names and log heights of a shard, in canonical chip order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct OrderedShape {
+    /// The `(chip_name, log_height)` pairs.
+    pub inner: Vec<(String, usize)>,
+}
+
+/// The error returned when a shard does not have exactly one opening per chip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("shard has {num_chips} chips but {num_openings} chip openings")]
+pub struct ShapeError {
+    /// The number of chips in the shard.
+    pub num_chips: usize,
+    /// The number of chip openings in the shard.
+    pub num_openings: usize,
+}
+
+impl OrderedShape {
+    /// The shape of a shard with the given chips and their openings, both in canonical order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ShapeError`] if there is not exactly one opening per chip.
+    pub fn from_openings<F: PrimeField32, EF>(
+        shard_chips: &BTreeSet<String>,
+        openings: &[ChipOpenedValues<F, EF>],
+    ) -> Result<Self, ShapeError> {
+        if shard_chips.len() != openings.len() {
+            return Err(ShapeError { num_chips: shard_chips.len(), num_openings: openings.len() });
+        }
+        let inner = shard_chips
+            .iter()
+            .zip(openings)
+            .map(|(name, values)| {
+                let height = values.height().as_canonical_u32() as usize;
+                (name.clone(), log2_ceil_usize(height))
+            })
+            .collect();
+        Ok(Self { inner })
+    }
+}
+
+impl<C: MachineConfig> ShardProof<C>
+where
+    C::F: PrimeField32,
+{
+    /// The shape of the shard, pairing each chip with the log of its (padded) trace height.
+    ///
+    /// Chips are ordered by name, which is the order of both `shard_chips` and the opened values.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ShapeError`] if the proof does not open exactly one chip per shard chip.
+    pub fn shape(&self) -> Result<OrderedShape, ShapeError> {
+        OrderedShape::from_openings(&self.shard_chips, &self.opened_values.chips)
+    }
+}
+
 /// The values of the chips in the shard at a random point.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShardOpenedValues<F, EF> {
@@ -75,6 +155,30 @@ pub struct ChipOpenedValues<F, EF> {
     pub degree: Point<F>,
 }
 
+impl<F, EF> ShardOpenedValues<F, EF> {
+    /// The total number of opened columns across all chips.
+    #[must_use]
+    pub fn total_columns(&self) -> usize {
+        self.chips.iter().map(ChipOpenedValues::total_columns).sum()
+    }
+}
+
+impl<F: Field, EF> ChipOpenedValues<F, EF> {
+    /// The height of the chip's trace, decoded from the big-endian bits of `degree`.
+    #[must_use]
+    pub fn height(&self) -> F {
+        decode_height(&self.degree)
+    }
+}
+
+impl<F, EF> ChipOpenedValues<F, EF> {
+    /// The number of opened preprocessed and main columns of the chip.
+    #[must_use]
+    pub fn total_columns(&self) -> usize {
+        self.preprocessed.local.len() + self.main.local.len()
+    }
+}
+
 /// The opening values for a given table section at a random point.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(bound(serialize = "T: Serialize"))]
@@ -98,3 +202,55 @@ impl<T> AirOpenedValues<T> {
         VerticalPair::new(a, b)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+
+    use super::*;
+
+    fn opening(height: u32, num_bits: usize) -> ChipOpenedValues<BabyBear, BabyBear> {
+        ChipOpenedValues {
+            preprocessed: AirOpenedValues { local: vec![], next: vec![] },
+            main: AirOpenedValues { local: vec![], next: vec![] },
+            local_cumulative_sum: BabyBear::zero(),
+            degree: (0..num_bits)
+                .rev()
+                .map(|i| BabyBear::from_canonical_u32((height >> i) & 1))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_total_columns() {
+        let mut cpu = opening(16, 6);
+        cpu.preprocessed.local = vec![BabyBear::one(); 2];
+        cpu.main.local = vec![BabyBear::one(); 5];
+        let mut memory = opening(8, 6);
+        memory.main.local = vec![BabyBear::one(); 3];
+        assert_eq!(cpu.total_columns(), 7);
+        assert_eq!(memory.total_columns(), 3);
+
+        let opened_values = ShardOpenedValues { chips: vec![cpu, memory] };
+        assert_eq!(opened_values.total_columns(), 10);
+        assert_eq!(ShardOpenedValues::<BabyBear, BabyBear> { chips: vec![] }.total_columns(), 0);
+    }
+
+    #[test]
+    fn test_shape_from_openings() {
+        let shard_chips = ["Cpu", "Memory"].map(String::from).into_iter().collect::<BTreeSet<_>>();
+        let shape =
+            OrderedShape::from_openings(&shard_chips, &[opening(16, 6), opening(8, 6)]).unwrap();
+        assert_eq!(shape.inner, vec![("Cpu".to_string(), 4), ("Memory".to_string(), 3)]);
+    }
+
+    #[test]
+    fn test_shape_length_mismatch() {
+        let shard_chips = ["Cpu", "Memory"].map(String::from).into_iter().collect::<BTreeSet<_>>();
+        assert_eq!(
+            OrderedShape::from_openings(&shard_chips, &[opening(16, 6)]),
+            Err(ShapeError { num_chips: 2, num_openings: 1 })
+        );
+    }
+}
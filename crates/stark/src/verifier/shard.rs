@@ -1,5 +1,8 @@
 use derive_where::derive_where;
-use std::{collections::BTreeSet, marker::PhantomData, ops::Deref};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    marker::PhantomData,
+};
 
 use hypercube_basefold::DefaultBasefoldConfig;
 use hypercube_commit::Rounds;
@@ -19,9 +22,50 @@ use thiserror::Error;
 use crate::{
     air::MachineAir, Chip, ChipOpenedValues, LogUpEvaluations, LogUpGkrVerifier,
     LogupGkrVerificationError, Machine, VerifierConstraintFolder,
+    VerifierPublicValuesConstraintFolder,
 };
 
-use super::{MachineConfig, MachineVerifyingKey, ShardOpenedValues, ShardProof};
+use super::{BabyBearPoseidon2, MachineConfig, MachineVerifyingKey, ShardOpenedValues, ShardProof};
+
+/// A cache of the constraint values of chips evaluated on an all-zero row.
+///
+/// The padded row adjustment of a chip folds these values with the constraint challenge `alpha`.
+/// The values themselves only depend on the chip and the public values, so when verifying many
+/// proofs with the same public values, the cache lets the chips be evaluated only once.
+///
+/// Values are keyed by chip name alone. A cache must therefore only be used with one machine and
+/// config: chips of another machine with the same names but different constraints would be
+/// adjusted with the wrong values.
+#[derive_where(Default, Clone)]
+pub struct PaddedRowCache<C: MachineConfig> {
+    /// The public values the cached constraint values were computed for.
+    public_values: Vec<C::F>,
+    /// The constraint values of each chip, indexed by chip name.
+    constraint_values: BTreeMap<String, Vec<C::EF>>,
+}
+
+impl<C: MachineConfig> PaddedRowCache<C> {
+    /// Returns the padded row adjustment of `chip` under `alpha`, evaluating the chip only if its
+    /// constraint values for `public_values` are not cached yet.
+    pub fn padded_row_adjustment<A>(
+        &mut self,
+        chip: &Chip<C::F, A>,
+        alpha: C::EF,
+        public_values: &[C::F],
+    ) -> C::EF
+    where
+        A: MachineAir<C::F> + for<'a> Air<VerifierConstraintFolder<'a, C>>,
+    {
+        if self.public_values != public_values {
+            self.public_values = public_values.to_vec();
+            self.constraint_values.clear();
+        }
+        let values = self.constraint_values.entry(chip.name()).or_insert_with(|| {
+            ShardVerifier::<C, A>::padded_row_constraint_values(chip, public_values)
+        });
+        values.iter().fold(C::EF::zero(), |acc, value| acc * alpha + *value)
+    }
+}
 
 /// A verifier for shard proofs.
 #[derive_where(Clone)]
@@ -32,6 +76,9 @@ pub struct ShardVerifier<C: MachineConfig, A> {
     pub machine: Machine<C::F, A>,
 }
 
+/// A shard verifier for the BabyBear Poseidon2 jagged basefold configuration.
+pub type BabyBearShardVerifier<A> = ShardVerifier<BabyBearPoseidon2, A>;
+
 /// An error that occurs during the verification of a shard proof.
 #[derive(Debug, Error)]
 pub enum ShardVerifierError<C: MachineConfig> {
@@ -56,9 +103,18 @@ pub enum ShardVerifierError<C: MachineConfig> {
     /// The GKR verification failed.
     #[error("GKR verification failed: {0}")]
     GkrVerificationFailed(LogupGkrVerificationError<C::EF>),
-    /// The public values verification failed.
-    #[error("public values verification failed")]
-    InvalidPublicValues,
+    /// There are fewer public values than the machine expects.
+    #[error("public values verification failed: expected {0} elements, got {1}")]
+    InvalidPublicValues(usize, usize),
+    /// The presence of a preprocessed commitment in the verifying key does not match the machine.
+    #[error("preprocessed commitment mismatch: machine expects one: {0}, vk has one: {1}")]
+    PreprocessedCommitmentMismatch(bool, bool),
+    /// The public constraints of a chip are not satisfied.
+    #[error("public constraints check failed for chip {0}")]
+    PublicConstraintsFailed(String),
+    /// The shard has no chips.
+    #[error("empty shard")]
+    EmptyShard,
 }
 
 /// An error that occurs when the shape of the openings does not match the expected shape.
@@ -85,6 +141,40 @@ impl<C: MachineConfig, A: MachineAir<C::F>> ShardVerifier<C, A> {
         self.pcs_verifier.challenger()
     }
 
+    /// Get a new challenger that has observed `seed`.
+    ///
+    /// The seed is observed as a slice of field elements into a fresh default challenger, before
+    /// anything else. A prover that wants to reproduce the transcript must observe the same seed
+    /// first, followed by the verifying key and then the shard proof as in [`Self::verify_shard`].
+    #[must_use]
+    pub fn challenger_with_seed(&self, seed: &[C::F]) -> C::Challenger {
+        let mut challenger = self.challenger();
+        challenger.observe_slice(seed);
+        challenger
+    }
+
+    /// Checks that the verifying key carries a preprocessed commitment exactly when the machine has
+    /// preprocessed chips, i.e. that it was built for a machine with the same preprocessed chips.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ShardVerifierError::PreprocessedCommitmentMismatch`] on inconsistency.
+    pub fn verify_preprocessed_commitment(
+        &self,
+        vk: &MachineVerifyingKey<C>,
+    ) -> Result<(), ShardVerifierError<C>> {
+        let machine_has_preprocessed = self.machine.has_preprocessed();
+        let vk_has_preprocessed = vk.preprocessed_commit.is_some();
+        if machine_has_preprocessed == vk_has_preprocessed {
+            Ok(())
+        } else {
+            Err(ShardVerifierError::PreprocessedCommitmentMismatch(
+                machine_has_preprocessed,
+                vk_has_preprocessed,
+            ))
+        }
+    }
+
     /// Compute the padded row adjustment for a chip.
     pub fn compute_padded_row_adjustment(
         chip: &Chip<C::F, A>,
@@ -97,16 +187,110 @@ impl<C: MachineConfig, A: MachineAir<C::F>> ShardVerifier<C, A> {
         let dummy_preprocessed_trace = vec![C::EF::zero(); chip.preprocessed_width()];
         let dummy_main_trace = vec![C::EF::zero(); chip.width()];
 
+        Self::eval_constraints_inner(
+            chip,
+            &dummy_preprocessed_trace,
+            &dummy_main_trace,
+            alpha,
+            public_values,
+            false,
+        )
+        .0
+    }
+
+    /// Computes the values of a chip's constraints on an all-zero row, in the order they are
+    /// asserted.
+    ///
+    /// Folding these values with `alpha` gives [`Self::compute_padded_row_adjustment`]; they do not
+    /// depend on `alpha`, so they can be reused across proofs with the same public values.
+    #[must_use]
+    pub fn padded_row_constraint_values(chip: &Chip<C::F, A>, public_values: &[C::F]) -> Vec<C::EF>
+    where
+        A: for<'a> Air<VerifierConstraintFolder<'a, C>>,
+    {
+        let dummy_preprocessed_trace = vec![C::EF::zero(); chip.preprocessed_width()];
+        let dummy_main_trace = vec![C::EF::zero(); chip.width()];
+
+        Self::eval_constraints_inner(
+            chip,
+            &dummy_preprocessed_trace,
+            &dummy_main_trace,
+            C::EF::zero(),
+            public_values,
+            true,
+        )
+        .1
+        .unwrap_or_default()
+    }
+
+    /// Evaluates the constraints for a chip and opening.
+    pub fn eval_constraints(
+        chip: &Chip<C::F, A>,
+        opening: &ChipOpenedValues<C::F, C::EF>,
+        alpha: C::EF,
+        public_values: &[C::F],
+    ) -> C::EF
+    where
+        A: for<'a> Air<VerifierConstraintFolder<'a, C>>,
+    {
+        Self::eval_constraints_inner(
+            chip,
+            &opening.preprocessed.local,
+            &opening.main.local,
+            alpha,
+            public_values,
+            false,
+        )
+        .0
+    }
+
+    /// Evaluates the constraints like [`Self::eval_constraints`], also returning the value of each
+    /// constraint in the order it was asserted.
+    ///
+    /// This is meant for debugging which constraint of a chip fails, and is not used during
+    /// verification.
+    #[must_use]
+    pub fn eval_constraints_with_values(
+        chip: &Chip<C::F, A>,
+        opening: &ChipOpenedValues<C::F, C::EF>,
+        alpha: C::EF,
+        public_values: &[C::F],
+    ) -> (C::EF, Vec<C::EF>)
+    where
+        A: for<'a> Air<VerifierConstraintFolder<'a, C>>,
+    {
+        let (accumulator, values) = Self::eval_constraints_inner(
+            chip,
+            &opening.preprocessed.local,
+            &opening.main.local,
+            alpha,
+            public_values,
+            true,
+        );
+        (accumulator, values.unwrap_or_default())
+    }
+
+    fn eval_constraints_inner(
+        chip: &Chip<C::F, A>,
+        preprocessed: &[C::EF],
+        main: &[C::EF],
+        alpha: C::EF,
+        public_values: &[C::F],
+        record_constraint_values: bool,
+    ) -> (C::EF, Option<Vec<C::EF>>)
+    where
+        A: for<'a> Air<VerifierConstraintFolder<'a, C>>,
+    {
         let default_challenge = C::EF::default();
 
         let mut folder = VerifierConstraintFolder::<C> {
             preprocessed: VerticalPair::new(
-                RowMajorMatrixView::new_row(&dummy_preprocessed_trace),
-                RowMajorMatrixView::new_row(&dummy_preprocessed_trace),
+                RowMajorMatrixView::new_row(preprocessed),
+                RowMajorMatrixView::new_row(preprocessed),
             ),
             main: VerticalPair::new(
-                RowMajorMatrixView::new_row(&dummy_main_trace),
-                RowMajorMatrixView::new_row(&dummy_main_trace),
+                RowMajorMatrixView::new_row(main),
+                RowMajorMatrixView::new_row(main),
             ),
             perm: VerticalPair::new(
                 RowMajorMatrixView::new_row(&[]),
@@ -120,40 +304,43 @@ impl<C: MachineConfig, A: MachineAir<C::F>> ShardVerifier<C, A> {
             alpha,
             accumulator: C::EF::zero(),
             public_values,
+            constraint_values: record_constraint_values.then(Vec::new),
             _marker: PhantomData,
         };
 
         chip.eval(&mut folder);
 
-        folder.accumulator
+        (folder.accumulator, folder.constraint_values)
     }
 
-    /// Evaluates the constraints for a chip and opening.
-    pub fn eval_constraints(
+    /// Checks the public constraints of a chip and returns its public interaction digest.
+    ///
+    /// Only the chip's [`MachineAir::eval_public_values`] is evaluated, over `public_values` alone
+    /// and without any trace rows. The constraints are folded with `alpha` and must vanish. The
+    /// returned digest is the sum of the chip's public interactions under `perm_challenges`, which
+    /// the trace interactions of the shard must cancel out.
+    pub fn verify_public_constraints(
         chip: &Chip<C::F, A>,
-        opening: &ChipOpenedValues<C::F, C::EF>,
-        alpha: C::EF,
         public_values: &[C::F],
-    ) -> C::EF
-    where
-        A: for<'a> Air<VerifierConstraintFolder<'a, C>>,
-    {
+        perm_challenges: &[C::EF],
+        alpha: C::EF,
+    ) -> Result<C::EF, ShardVerifierError<C>> {
         let default_challenge = C::EF::default();
 
-        let mut folder = VerifierConstraintFolder::<C> {
+        let mut folder = VerifierPublicValuesConstraintFolder::<C> {
             preprocessed: VerticalPair::new(
-                RowMajorMatrixView::new_row(&opening.preprocessed.local),
-                RowMajorMatrixView::new_row(&opening.preprocessed.local),
+                RowMajorMatrixView::new_row(&[]),
+                RowMajorMatrixView::new_row(&[]),
             ),
             main: VerticalPair::new(
-                RowMajorMatrixView::new_row(&opening.main.local),
-                RowMajorMatrixView::new_row(&opening.main.local),
+                RowMajorMatrixView::new_row(&[]),
+                RowMajorMatrixView::new_row(&[]),
             ),
             perm: VerticalPair::new(
                 RowMajorMatrixView::new_row(&[]),
                 RowMajorMatrixView::new_row(&[]),
             ),
-            perm_challenges: &[],
+            perm_challenges,
             local_cumulative_sum: &default_challenge,
             is_first_row: default_challenge,
             is_last_row: default_challenge,
@@ -161,12 +348,17 @@ impl<C: MachineConfig, A: MachineAir<C::F>> ShardVerifier<C, A> {
             alpha,
             accumulator: C::EF::zero(),
             public_values,
+            local_interaction_digest: C::EF::zero(),
             _marker: PhantomData,
         };
 
-        chip.eval(&mut folder);
+        chip.air.eval_public_values(&mut folder);
+
+        if folder.accumulator != C::EF::zero() {
+            return Err(ShardVerifierError::PublicConstraintsFailed(chip.name()));
+        }
 
-        folder.accumulator
+        Ok(folder.local_interaction_digest)
     }
 
     fn verify_opening_shape(
@@ -204,6 +396,34 @@ impl<C: MachineConfig, A: MachineAir<C::F>> ShardVerifier<C, A> {
         public_values: &[C::F],
         challenger: &mut C::Challenger,
     ) -> Result<(), ShardVerifierError<C>>
+    where
+        A: for<'a> Air<VerifierConstraintFolder<'a, C>>,
+    {
+        self.verify_zerocheck_with_cache(
+            shard_chips,
+            opened_values,
+            gkr_evaluations,
+            proof,
+            public_values,
+            challenger,
+            &mut PaddedRowCache::default(),
+        )
+    }
+
+    /// Verify the zerocheck proof like [`Self::verify_zerocheck`], taking the padded row
+    /// adjustments from `cache`.
+    #[allow(clippy::too_many_arguments)]
+    #[allow(clippy::type_complexity)]
+    pub fn verify_zerocheck_with_cache(
+        &self,
+        shard_chips: &BTreeSet<Chip<C::F, A>>,
+        opened_values: &ShardOpenedValues<C::F, C::EF>,
+        gkr_evaluations: &LogUpEvaluations<C::EF>,
+        proof: &ShardProof<C>,
+        public_values: &[C::F],
+        challenger: &mut C::Challenger,
+        cache: &mut PaddedRowCache<C>,
+    ) -> Result<(), ShardVerifierError<C>>
     where
         A: for<'a> Air<VerifierConstraintFolder<'a, C>>,
     {
@@ -244,8 +464,7 @@ impl<C: MachineConfig, A: MachineAir<C::F>> ShardVerifier<C, A> {
 
             let geq_val = full_geq(&openings.degree, &point_extended);
 
-            let padded_row_adjustment =
-                Self::compute_padded_row_adjustment(chip, alpha, public_values);
+            let padded_row_adjustment = cache.padded_row_adjustment(chip, alpha, public_values);
 
             let constraint_eval = Self::eval_constraints(chip, openings, alpha, public_values)
                 - padded_row_adjustment * geq_val;
@@ -275,17 +494,8 @@ impl<C: MachineConfig, A: MachineAir<C::F>> ShardVerifier<C, A> {
             .values()
             .map(|chip_evaluation| {
                 chip_evaluation
-                    .main_trace_evaluations
-                    .deref()
-                    .iter()
+                    .all_evaluations()
                     .copied()
-                    .chain(
-                        chip_evaluation
-                            .preprocessed_trace_evaluations
-                            .as_ref()
-                            .iter()
-                            .flat_map(|&evals| evals.deref().iter().copied()),
-                    )
                     .zip(gkr_batch_open_challenge.powers())
                     .map(|(opening, power)| opening * power)
                     .sum::<C::EF>()
@@ -322,7 +532,17 @@ impl<C: MachineConfig, A: MachineAir<C::F>> ShardVerifier<C, A> {
     }
 
     /// Verify a shard proof.
-    #[allow(clippy::too_many_lines)]
+    ///
+    /// The challenger is used as given, so it may have been seeded beforehand (e.g. by
+    /// [`Self::challenger_with_seed`] or by observing the verifying key). The verifier then observes
+    /// the public values, the main commitment, and the chip heights, in that order, before sampling
+    /// any challenges.
+    ///
+    /// With the `metrics` feature, the `logup_gkr`, `zerocheck` and `pcs_open` phases are wrapped in
+    /// `tracing` spans, whose durations are reported by subscribers that record span timings.
+    ///
+    /// A shard without chips has no trace to commit to, so such proofs are rejected with
+    /// [`ShardVerifierError::EmptyShard`] rather than verified vacuously.
     pub fn verify_shard(
         &self,
         vk: &MachineVerifyingKey<C>,
@@ -331,6 +551,27 @@ impl<C: MachineConfig, A: MachineAir<C::F>> ShardVerifier<C, A> {
     ) -> Result<(), ShardVerifierError<C>>
     where
         A: for<'a> Air<VerifierConstraintFolder<'a, C>>,
+    {
+        self.verify_shard_with_cache(vk, proof, challenger, &mut PaddedRowCache::default())
+    }
+
+    /// Verify a shard proof like [`Self::verify_shard`], reusing the chips' padded row constraint
+    /// values from `cache`.
+    ///
+    /// When verifying many proofs with the same public values, passing the same cache avoids
+    /// evaluating every chip on a padded row again for each proof. The cache must not be shared
+    /// with verifiers of other machines, see [`PaddedRowCache`].
+    #[allow(clippy::too_many_lines)]
+    pub fn verify_shard_with_cache(
+        &self,
+        vk: &MachineVerifyingKey<C>,
+        proof: &ShardProof<C>,
+        challenger: &mut C::Challenger,
+        cache: &mut PaddedRowCache<C>,
+    ) -> Result<(), ShardVerifierError<C>>
+    where
+        A: for<'a> Air<VerifierConstraintFolder<'a, C>>
+            + for<'a> Air<VerifierPublicValuesConstraintFolder<'a, C>>,
     {
         let ShardProof {
             shard_chips,
@@ -341,6 +582,15 @@ impl<C: MachineConfig, A: MachineAir<C::F>> ShardVerifier<C, A> {
             public_values,
             logup_gkr_proof,
         } = proof;
+        if shard_chips.is_empty() || opened_values.chips.is_empty() {
+            return Err(ShardVerifierError::EmptyShard);
+        }
+        if public_values.len() < self.machine.num_pv_elts() {
+            return Err(ShardVerifierError::InvalidPublicValues(
+                self.machine.num_pv_elts(),
+                public_values.len(),
+            ));
+        }
         // Observe the public values.
         challenger.observe_slice(&public_values[0..self.machine.num_pv_elts()]);
         // Observe the main commitment.
@@ -349,19 +599,16 @@ impl<C: MachineConfig, A: MachineAir<C::F>> ShardVerifier<C, A> {
         let mut heights: Vec<C::F> = Vec::new();
         for chip_values in opened_values.chips.iter() {
             assert!(chip_values.degree.len() <= 29);
-            let acc = chip_values.degree.iter().fold(C::F::zero(), |acc, &x| x + C::F::two() * acc);
+            let acc = chip_values.height();
             heights.push(acc);
             challenger.observe(acc);
         }
 
         let alpha = challenger.sample_ext_element::<C::EF>();
         let beta = challenger.sample_ext_element::<C::EF>();
-        let _pv_challenge = challenger.sample_ext_element::<C::EF>();
-
-        // There are no public constraints for the recursion machine.
+        let pv_challenge = challenger.sample_ext_element::<C::EF>();
 
         let max_log_row_count = self.pcs_verifier.max_log_row_count;
-        let cumulative_sum = C::EF::zero();
 
         let shard_chips = self
             .machine
@@ -371,9 +618,24 @@ impl<C: MachineConfig, A: MachineAir<C::F>> ShardVerifier<C, A> {
             .cloned()
             .collect::<BTreeSet<_>>();
 
+        // The trace interactions must cancel out the public interactions of the shard.
+        let mut cumulative_sum = C::EF::zero();
+        if self.machine.has_public_constraints() {
+            for chip in shard_chips.iter().filter(|chip| chip.air.has_public_constraints()) {
+                cumulative_sum -= Self::verify_public_constraints(
+                    chip,
+                    public_values,
+                    &[alpha, beta],
+                    pv_challenge,
+                )?;
+            }
+        }
+
         let degrees = opened_values.chips.iter().map(|x| x.degree.clone()).collect::<Vec<_>>();
 
         // Verify the logup GKR proof.
+        #[cfg(feature = "metrics")]
+        let logup_gkr_span = tracing::info_span!("logup_gkr").entered();
         LogUpGkrVerifier::<_, _, A>::verify_logup_gkr(
             &shard_chips,
             &degrees,
@@ -385,18 +647,27 @@ impl<C: MachineConfig, A: MachineAir<C::F>> ShardVerifier<C, A> {
             challenger,
         )
         .map_err(ShardVerifierError::GkrVerificationFailed)?;
+        #[cfg(feature = "metrics")]
+        logup_gkr_span.exit();
 
         // Verify the zerocheck proof.
-        self.verify_zerocheck(
+        #[cfg(feature = "metrics")]
+        let zerocheck_span = tracing::info_span!("zerocheck").entered();
+        self.verify_zerocheck_with_cache(
             &shard_chips,
             opened_values,
             &logup_gkr_proof.logup_evaluations,
             proof,
             public_values,
             challenger,
+            cache,
         )?;
+        #[cfg(feature = "metrics")]
+        zerocheck_span.exit();
 
         // Verify the opening proof.
+        #[cfg(feature = "metrics")]
+        let _pcs_open_span = tracing::info_span!("pcs_open").entered();
         let (preprocessed_openings_for_proof, main_openings_for_proof): (Vec<_>, Vec<_>) = proof
             .opened_values
             .chips
@@ -414,10 +685,11 @@ impl<C: MachineConfig, A: MachineAir<C::F>> ShardVerifier<C, A> {
             .map(|x| x.local.iter().copied().collect::<MleEval<_>>())
             .collect::<Evaluations<_>>();
 
-        let filtered_preprocessed_openings = preprocessed_openings
-            .into_iter()
-            .filter(|x| !x.is_empty())
-            .map(|x| x.iter().copied().collect::<MleEval<_>>())
+        let filtered_preprocessed_openings = shard_chips
+            .iter()
+            .zip_eq(preprocessed_openings)
+            .filter(|(chip, _)| chip.has_preprocessed())
+            .map(|(_, x)| x.iter().copied().collect::<MleEval<_>>())
             .collect::<Evaluations<_>>();
 
         let preprocessed_column_count = filtered_preprocessed_openings
@@ -428,6 +700,7 @@ impl<C: MachineConfig, A: MachineAir<C::F>> ShardVerifier<C, A> {
         let main_column_count =
             main_openings.iter().map(|table_openings| table_openings.len()).collect::<Vec<_>>();
 
+        self.verify_preprocessed_commitment(vk)?;
         let only_has_main_commitment = vk.preprocessed_commit.is_none();
 
         let (commitments, column_counts, openings) = if only_has_main_commitment {
@@ -479,3 +752,353 @@ where
         Self { pcs_verifier, machine }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use hypercube_basefold::BasefoldProof;
+    use hypercube_jagged::{
+        BabyBearPoseidon2, JaggedConfig, JaggedLittlePolynomialVerifierParams, JaggedPcsProof,
+        JaggedSumcheckEvalProof,
+    };
+    use hypercube_multilinear::Point;
+    use hypercube_stacked::StackedPcsProof;
+    use hypercube_sumcheck::PartialSumcheckProof;
+    use p3_air::AirBuilder;
+    use p3_baby_bear::BabyBear;
+    use p3_field::Field;
+    use p3_matrix::Matrix;
+    use std::{collections::BTreeMap, hint::black_box, time::Instant};
+
+    use crate::{
+        air::{AirInteraction, InteractionScope, MachineAirBuilder, MessageBuilder},
+        septic_digest::SepticDigest,
+        AirOpenedValues, InteractionKind, LogUpEvaluations, LogUpGkrOutput, LogupGkrProof,
+        LogupGkrVerificationError, DIGEST_SIZE,
+    };
+
+    use super::*;
+
+    type EF = <BabyBearPoseidon2 as JaggedConfig>::EF;
+
+    /// A toy chip with one column, constrained to be both one and two so that its constraints do
+    /// not hold at a random opening point. Its public values `[a, b, c]` must satisfy `a + b = c`,
+    /// and it sends `c`.
+    struct ToyAir;
+
+    impl<F: Field> BaseAir<F> for ToyAir {
+        fn width(&self) -> usize {
+            1
+        }
+    }
+
+    impl<F: Field> MachineAir<F> for ToyAir {
+        fn name(&self) -> String {
+            "Toy".to_string()
+        }
+
+        fn has_public_constraints(&self) -> bool {
+            true
+        }
+
+        fn eval_public_values<AB: MachineAirBuilder<F = F>>(&self, builder: &mut AB) {
+            let [a, b, c]: [AB::Expr; 3] = [0, 1, 2].map(|i| builder.public_values()[i].into());
+            builder.assert_eq(a + b, c.clone());
+            builder.send(
+                AirInteraction::new(vec![c], AB::Expr::one(), InteractionKind::Memory),
+                InteractionScope::Local,
+            );
+        }
+    }
+
+    impl<AB: AirBuilder> Air<AB> for ToyAir {
+        fn eval(&self, builder: &mut AB) {
+            let main = builder.main();
+            let local = main.row_slice(0)[0];
+            builder.assert_one(local);
+            builder.assert_eq(local, AB::Expr::two());
+        }
+    }
+
+    fn toy_verifier() -> ShardVerifier<BabyBearPoseidon2, ToyAir> {
+        ShardVerifier::from_basefold_parameters(1, 21, 21, Machine::new(vec![Chip::new(ToyAir)], 3))
+    }
+
+    fn toy_vk() -> MachineVerifyingKey<BabyBearPoseidon2> {
+        MachineVerifyingKey {
+            pc_start: BabyBear::zero(),
+            initial_global_cumulative_sum: SepticDigest::zero(),
+            preprocessed_commit: None,
+            preprocessed_chip_information: BTreeMap::new(),
+        }
+    }
+
+    fn public_values(values: [u32; 3]) -> Vec<BabyBear> {
+        values.map(BabyBear::from_canonical_u32).to_vec()
+    }
+
+    fn empty_sumcheck_proof<K: Field>() -> PartialSumcheckProof<K> {
+        PartialSumcheckProof {
+            univariate_polys: vec![],
+            claimed_sum: K::zero(),
+            point_and_eval: (Point::default(), K::zero()),
+        }
+    }
+
+    /// A shard proof of the toy chip whose GKR output cancels the public interaction digest of
+    /// `public_values`, and which is otherwise empty.
+    fn toy_shard_proof(
+        verifier: &BabyBearShardVerifier<ToyAir>,
+        public_values: Vec<BabyBear>,
+    ) -> ShardProof<BabyBearPoseidon2> {
+        let mut proof = ShardProof::<BabyBearPoseidon2> {
+            public_values,
+            main_commitment: [BabyBear::zero(); DIGEST_SIZE],
+            logup_gkr_proof: LogupGkrProof {
+                circuit_output: LogUpGkrOutput {
+                    numerator: Mle::from(vec![EF::zero(); 2]),
+                    denominator: Mle::from(vec![EF::one(); 2]),
+                },
+                round_proofs: vec![],
+                logup_evaluations: LogUpEvaluations {
+                    point: Point::default(),
+                    chip_openings: BTreeMap::new(),
+                },
+            },
+            zerocheck_proof: empty_sumcheck_proof(),
+            opened_values: ShardOpenedValues {
+                chips: vec![ChipOpenedValues {
+                    preprocessed: AirOpenedValues { local: vec![], next: vec![] },
+                    main: AirOpenedValues { local: vec![EF::one()], next: vec![EF::one()] },
+                    local_cumulative_sum: EF::zero(),
+                    degree: Point::from_usize(4, 3),
+                }],
+            },
+            evaluation_proof: JaggedPcsProof {
+                stacked_pcs_proof: StackedPcsProof {
+                    pcs_proof: BasefoldProof {
+                        univariate_messages: vec![],
+                        fri_commitments: vec![],
+                        component_polynomials_query_openings: vec![],
+                        query_phase_openings: vec![],
+                        final_poly: EF::zero(),
+                        pow_witness: BabyBear::zero(),
+                    },
+                    batch_evaluations: Rounds::new(),
+                },
+                sumcheck_proof: empty_sumcheck_proof(),
+                jagged_eval_proof: JaggedSumcheckEvalProof {
+                    branching_program_evals: vec![],
+                    partial_sumcheck_proof: empty_sumcheck_proof(),
+                },
+                params: JaggedLittlePolynomialVerifierParams {
+                    col_prefix_sums: vec![],
+                    max_log_row_count: 21,
+                },
+            },
+            shard_chips: BTreeSet::from(["Toy".to_string()]),
+        };
+
+        // Replay the transcript up to the permutation challenges.
+        let mut challenger = verifier.challenger();
+        challenger.observe_slice(&proof.public_values);
+        challenger.observe(proof.main_commitment);
+        for chip_values in &proof.opened_values.chips {
+            challenger.observe(chip_values.height());
+        }
+        let alpha = challenger.sample_ext_element::<EF>();
+        let beta = challenger.sample_ext_element::<EF>();
+        let pv_challenge = challenger.sample_ext_element::<EF>();
+
+        let digest = BabyBearShardVerifier::<ToyAir>::verify_public_constraints(
+            &Chip::new(ToyAir),
+            &proof.public_values,
+            &[alpha, beta],
+            pv_challenge,
+        )
+        .unwrap();
+        proof.logup_gkr_proof.circuit_output.numerator = Mle::from(vec![-digest, EF::zero()]);
+        proof
+    }
+
+    #[test]
+    fn test_seeded_challenger_changes_alpha() {
+        let verifier = toy_verifier();
+        let seed = [1, 2, 3].map(BabyBear::from_canonical_u32);
+
+        let alpha = verifier.challenger().sample_ext_element::<EF>();
+        let seeded_alpha = verifier.challenger_with_seed(&seed).sample_ext_element::<EF>();
+        assert_ne!(alpha, seeded_alpha);
+
+        // Seeding is the same as observing the seed first, and an empty seed changes nothing.
+        let mut challenger = verifier.challenger();
+        challenger.observe_slice(&seed);
+        assert_eq!(challenger.sample_ext_element::<EF>(), seeded_alpha);
+        assert_eq!(verifier.challenger_with_seed(&[]).sample_ext_element::<EF>(), alpha);
+    }
+
+    #[test]
+    fn test_vk_with_unexpected_preprocessed_commitment() {
+        let machine = Machine::new(vec![Chip::new(ToyAir)], 3);
+        assert!(!machine.has_preprocessed());
+        let verifier =
+            ShardVerifier::<BabyBearPoseidon2, _>::from_basefold_parameters(1, 21, 21, machine);
+
+        let mut vk = toy_vk();
+        assert!(verifier.verify_preprocessed_commitment(&vk).is_ok());
+
+        vk.preprocessed_commit = Some([BabyBear::one(); DIGEST_SIZE]);
+        assert!(matches!(
+            verifier.verify_preprocessed_commitment(&vk),
+            Err(ShardVerifierError::PreprocessedCommitmentMismatch(false, true))
+        ));
+    }
+
+    #[test]
+    fn test_eval_constraints_with_values() {
+        let chip = Chip::<BabyBear, _>::new(ToyAir);
+        let opening = ChipOpenedValues::<BabyBear, EF> {
+            preprocessed: AirOpenedValues { local: vec![], next: vec![] },
+            main: AirOpenedValues { local: vec![EF::one()], next: vec![EF::one()] },
+            local_cumulative_sum: EF::zero(),
+            degree: Point::from_usize(1, 2),
+        };
+        let alpha = EF::from_canonical_u32(7);
+
+        // The column is one, so `assert_one` holds and `assert_eq(local, 2)` is off by minus one.
+        let (accumulator, values) = BabyBearShardVerifier::<ToyAir>::eval_constraints_with_values(
+            &chip,
+            &opening,
+            alpha,
+            &[],
+        );
+        assert_eq!(values, vec![EF::zero(), -EF::one()]);
+        assert_eq!(accumulator, -EF::one());
+        assert_eq!(
+            accumulator,
+            BabyBearShardVerifier::<ToyAir>::eval_constraints(&chip, &opening, alpha, &[])
+        );
+    }
+
+    #[test]
+    fn test_public_constraints_satisfied() {
+        let chip = Chip::<BabyBear, _>::new(ToyAir);
+        let (alpha, beta) = (EF::from_canonical_u32(11), EF::from_canonical_u32(13));
+        let pv_challenge = EF::from_canonical_u32(17);
+
+        let digest = BabyBearShardVerifier::<ToyAir>::verify_public_constraints(
+            &chip,
+            &public_values([2, 3, 5]),
+            &[alpha, beta],
+            pv_challenge,
+        )
+        .unwrap();
+
+        let denominator = alpha
+            + EF::from_canonical_usize(InteractionKind::Memory as usize)
+            + beta * EF::from_canonical_u32(5);
+        assert_eq!(digest, denominator.inverse());
+    }
+
+    #[test]
+    fn test_public_constraints_violated() {
+        let chip = Chip::<BabyBear, _>::new(ToyAir);
+        let (alpha, beta) = (EF::from_canonical_u32(11), EF::from_canonical_u32(13));
+        let pv_challenge = EF::from_canonical_u32(17);
+
+        let result = BabyBearShardVerifier::<ToyAir>::verify_public_constraints(
+            &chip,
+            &public_values([2, 3, 6]),
+            &[alpha, beta],
+            pv_challenge,
+        );
+        assert!(
+            matches!(result, Err(ShardVerifierError::PublicConstraintsFailed(name)) if name == "Toy")
+        );
+    }
+
+    #[test]
+    fn test_verify_shard_checks_public_interaction_digest() {
+        let verifier = toy_verifier();
+        let vk = toy_vk();
+        let verify = |proof: &ShardProof<BabyBearPoseidon2>| {
+            verifier.verify_shard(&vk, proof, &mut verifier.challenger())
+        };
+
+        // The GKR output cancels the digest, so verification gets past the cumulative sum check
+        // and only fails at the GKR rounds, which the toy proof does not contain.
+        let mut proof = toy_shard_proof(&verifier, public_values([2, 3, 5]));
+        assert!(matches!(
+            verify(&proof),
+            Err(ShardVerifierError::GkrVerificationFailed(error))
+                if !matches!(error, LogupGkrVerificationError::CumulativeSumMismatch(..))
+        ));
+
+        // Public values that satisfy the constraint but have a different digest are rejected.
+        proof.public_values = public_values([2, 4, 6]);
+        assert!(matches!(
+            verify(&proof),
+            Err(ShardVerifierError::GkrVerificationFailed(
+                LogupGkrVerificationError::CumulativeSumMismatch(..)
+            ))
+        ));
+
+        // Public values that violate the constraint are rejected before the GKR proof is read.
+        proof.public_values = public_values([2, 3, 6]);
+        assert!(matches!(
+            verify(&proof),
+            Err(ShardVerifierError::PublicConstraintsFailed(name)) if name == "Toy"
+        ));
+    }
+
+    #[test]
+    fn test_padded_row_cache_matches_uncached() {
+        let chip = Chip::<BabyBear, _>::new(ToyAir);
+        let mut cache = PaddedRowCache::<BabyBearPoseidon2>::default();
+        for values in [[2, 3, 5], [1, 1, 2]] {
+            let public_values = public_values(values);
+            for alpha in [7, 11].map(EF::from_canonical_u32) {
+                assert_eq!(
+                    cache.padded_row_adjustment(&chip, alpha, &public_values),
+                    BabyBearShardVerifier::<ToyAir>::compute_padded_row_adjustment(
+                        &chip,
+                        alpha,
+                        &public_values
+                    )
+                );
+            }
+        }
+    }
+
+    /// Times the padded row adjustment with and without a cache. Run with
+    /// `cargo test --release -p hypercube-stark bench_padded_row_cache -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_padded_row_cache() {
+        const NUM_PROOFS: u32 = 100_000;
+        let chip = Chip::<BabyBear, _>::new(ToyAir);
+        let public_values = public_values([2, 3, 5]);
+
+        let start = Instant::now();
+        for i in 0..NUM_PROOFS {
+            black_box(BabyBearShardVerifier::<ToyAir>::compute_padded_row_adjustment(
+                &chip,
+                EF::from_canonical_u32(i),
+                &public_values,
+            ));
+        }
+        let uncached = start.elapsed();
+
+        let mut cache = PaddedRowCache::<BabyBearPoseidon2>::default();
+        let start = Instant::now();
+        for i in 0..NUM_PROOFS {
+            black_box(cache.padded_row_adjustment(
+                &chip,
+                EF::from_canonical_u32(i),
+                &public_values,
+            ));
+        }
+        let cached = start.elapsed();
+
+        println!("{NUM_PROOFS} adjustments: uncached {uncached:?}, cached {cached:?}");
+    }
+}
@@ -1,5 +1,6 @@
 use thiserror::Error;
 
+use hypercube_algebra::UnivariatePolynomial;
 use hypercube_multilinear::Point;
 use p3_challenger::FieldChallenger;
 use p3_field::{ExtensionField, Field};
@@ -16,6 +17,8 @@ pub enum SumcheckError {
     InconsistencyWithClaimedSum,
     #[error("inconsistency of proof with evaluation claim")]
     InconsistencyWithEval,
+    #[error("round {round} polynomial has degree {degree}, exceeding the maximum")]
+    DegreeTooHigh { round: usize, degree: usize },
 }
 
 /// Verifies that a PartialSumcheckProof is correct up until the evaluation claim.
@@ -85,3 +88,211 @@ pub fn partially_verify_sumcheck_proof<
 
     Ok(())
 }
+
+/// Verifies a PartialSumcheckProof like [partially_verify_sumcheck_proof], additionally rejecting
+/// any round polynomial with more than `max_degree + 1` coefficients.
+pub fn partially_verify_sumcheck_proof_with_degree<
+    F: Field,
+    EF: ExtensionField<F>,
+    Challenger: FieldChallenger<F>,
+>(
+    proof: &PartialSumcheckProof<EF>,
+    challenger: &mut Challenger,
+    max_degree: usize,
+) -> Result<(), SumcheckError> {
+    for (round, poly) in proof.univariate_polys.iter().enumerate() {
+        let degree = poly.coefficients.len().saturating_sub(1);
+        if degree > max_degree {
+            return Err(SumcheckError::DegreeTooHigh { round, degree });
+        }
+    }
+
+    partially_verify_sumcheck_proof(proof, challenger)
+}
+
+/// Verifies a batch of sumcheck proofs over the same random point by combining them, with powers of
+/// `batching_challenge`, into a single proof of the combined claim.
+///
+/// The combined proof is verified like [partially_verify_sumcheck_proof] and returned, so that the
+/// caller can check its evaluation claim against the same combination of the component
+/// polynomials' evaluations.
+pub fn partially_verify_batched_sumcheck_proofs<
+    F: Field,
+    EF: ExtensionField<F>,
+    Challenger: FieldChallenger<F>,
+>(
+    proofs: &[PartialSumcheckProof<EF>],
+    batching_challenge: EF,
+    challenger: &mut Challenger,
+) -> Result<PartialSumcheckProof<EF>, SumcheckError> {
+    let (first, rest) = proofs.split_first().ok_or(SumcheckError::InvalidProofShape)?;
+    let num_variables = first.univariate_polys.len();
+    for proof in rest {
+        if proof.univariate_polys.len() != num_variables
+            || proof.point_and_eval.0 != first.point_and_eval.0
+        {
+            return Err(SumcheckError::InvalidProofShape);
+        }
+    }
+
+    let powers = batching_challenge.powers().take(proofs.len()).collect::<Vec<_>>();
+    let univariate_polys = (0..num_variables)
+        .map(|round| {
+            let num_coefficients = proofs
+                .iter()
+                .map(|proof| proof.univariate_polys[round].coefficients.len())
+                .max()
+                .unwrap_or_default();
+            let mut coefficients = vec![EF::zero(); num_coefficients];
+            for (proof, &power) in proofs.iter().zip(powers.iter()) {
+                for (acc, &coefficient) in
+                    coefficients.iter_mut().zip(proof.univariate_polys[round].coefficients.iter())
+                {
+                    *acc += power * coefficient;
+                }
+            }
+            UnivariatePolynomial::new(coefficients)
+        })
+        .collect();
+    let claimed_sum =
+        proofs.iter().zip(powers.iter()).map(|(proof, &power)| power * proof.claimed_sum).sum();
+    let eval = proofs
+        .iter()
+        .zip(powers.iter())
+        .map(|(proof, &power)| power * proof.point_and_eval.1)
+        .sum();
+
+    let combined = PartialSumcheckProof {
+        univariate_polys,
+        claimed_sum,
+        point_and_eval: (first.point_and_eval.0.clone(), eval),
+    };
+    partially_verify_sumcheck_proof(&combined, challenger)?;
+
+    Ok(combined)
+}
+
+#[cfg(test)]
+mod tests {
+    use hypercube_merkle_tree::{my_bb_16_perm, Perm};
+    use p3_baby_bear::BabyBear;
+    use p3_challenger::{CanObserve, DuplexChallenger};
+    use p3_field::AbstractField;
+
+    use super::*;
+
+    type Challenger = DuplexChallenger<BabyBear, Perm, 16, 8>;
+
+    /// A valid proof for the sum of `3 + 5 * x` over `x` in `{0, 1}`.
+    fn linear_proof() -> PartialSumcheckProof<BabyBear> {
+        let poly = UnivariatePolynomial::new(vec![3, 5].map(BabyBear::from_canonical_u32).to_vec());
+        let mut challenger = Challenger::new(my_bb_16_perm());
+        challenger.observe_slice(&poly.coefficients);
+        let alpha: BabyBear = challenger.sample_ext_element();
+        let mut point = Point::default();
+        point.add_dimension(alpha);
+        let eval = poly.eval_at_point(alpha);
+        PartialSumcheckProof {
+            univariate_polys: vec![poly],
+            claimed_sum: BabyBear::from_canonical_u32(11),
+            point_and_eval: (point, eval),
+        }
+    }
+
+    #[test]
+    fn test_degree_within_bound() {
+        let proof = linear_proof();
+        let mut challenger = Challenger::new(my_bb_16_perm());
+        assert_eq!(
+            partially_verify_sumcheck_proof_with_degree::<BabyBear, _, _>(
+                &proof,
+                &mut challenger,
+                1
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_degree_too_high() {
+        let proof = linear_proof();
+        let mut challenger = Challenger::new(my_bb_16_perm());
+        assert_eq!(
+            partially_verify_sumcheck_proof_with_degree::<BabyBear, _, _>(
+                &proof,
+                &mut challenger,
+                0
+            ),
+            Err(SumcheckError::DegreeTooHigh { round: 0, degree: 1 })
+        );
+    }
+
+    /// Valid single-round proofs for the sums of `3 + 5 * x` and `2 + 7 * x`, at the point sampled
+    /// by the verifier of their combination with `batching_challenge`.
+    fn batched_proofs(batching_challenge: BabyBear) -> Vec<PartialSumcheckProof<BabyBear>> {
+        let polys = [[3, 5], [2, 7]]
+            .map(|coefficients| coefficients.map(BabyBear::from_canonical_u32).to_vec())
+            .map(UnivariatePolynomial::new);
+        let combined_constant =
+            polys[0].coefficients[0] + batching_challenge * polys[1].coefficients[0];
+        let combined_slope =
+            polys[0].coefficients[1] + batching_challenge * polys[1].coefficients[1];
+        let mut challenger = Challenger::new(my_bb_16_perm());
+        challenger.observe_slice(&[combined_constant, combined_slope]);
+        let alpha: BabyBear = challenger.sample_ext_element();
+        let mut point = Point::default();
+        point.add_dimension(alpha);
+        polys
+            .into_iter()
+            .map(|poly| PartialSumcheckProof {
+                claimed_sum: poly.eval_one_plus_eval_zero(),
+                point_and_eval: (point.clone(), poly.eval_at_point(alpha)),
+                univariate_polys: vec![poly],
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_batched_two_claims() {
+        let batching_challenge = BabyBear::from_canonical_u32(9);
+        let proofs = batched_proofs(batching_challenge);
+        let mut challenger = Challenger::new(my_bb_16_perm());
+        let combined =
+            partially_verify_batched_sumcheck_proofs(&proofs, batching_challenge, &mut challenger)
+                .unwrap();
+        assert_eq!(
+            combined.univariate_polys[0].coefficients,
+            [21, 68].map(BabyBear::from_canonical_u32)
+        );
+        assert_eq!(combined.claimed_sum, BabyBear::from_canonical_u32(11 + 9 * 11));
+        assert_eq!(combined.point_and_eval.0, proofs[0].point_and_eval.0);
+        assert_eq!(
+            combined.point_and_eval.1,
+            proofs[0].point_and_eval.1 + batching_challenge * proofs[1].point_and_eval.1
+        );
+    }
+
+    #[test]
+    fn test_batched_invalid_claims() {
+        let batching_challenge = BabyBear::from_canonical_u32(9);
+        let verify = |proofs: &[PartialSumcheckProof<BabyBear>]| {
+            let mut challenger = Challenger::new(my_bb_16_perm());
+            partially_verify_batched_sumcheck_proofs(proofs, batching_challenge, &mut challenger)
+                .map(|_| ())
+        };
+
+        assert_eq!(verify(&[]), Err(SumcheckError::InvalidProofShape));
+
+        let mut proofs = batched_proofs(batching_challenge);
+        proofs[1].claimed_sum += BabyBear::one();
+        assert_eq!(verify(&proofs), Err(SumcheckError::InconsistencyWithClaimedSum));
+
+        let mut proofs = batched_proofs(batching_challenge);
+        proofs[1].point_and_eval.1 += BabyBear::one();
+        assert_eq!(verify(&proofs), Err(SumcheckError::InconsistencyWithEval));
+
+        let mut proofs = batched_proofs(batching_challenge);
+        proofs[1].point_and_eval.0 = linear_proof().point_and_eval.0;
+        assert_eq!(verify(&proofs), Err(SumcheckError::InvalidProofShape));
+    }
+}
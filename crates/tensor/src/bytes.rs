@@ -0,0 +1,110 @@
+use std::mem::size_of;
+
+use bytemuck::{Pod, PodCastError};
+use hypercube_alloc::CpuBackend;
+use thiserror::Error;
+
+use crate::{Dimensions, DimensionsError, Tensor, TensorView};
+
+/// An error converting between a tensor and its raw bytes.
+#[derive(Debug, Clone, Copy, Error)]
+pub enum TensorBytesError {
+    /// The bytes cannot be cast to elements of the tensor, because of their length or alignment.
+    #[error("invalid byte cast: {0}")]
+    Cast(PodCastError),
+    /// The sizes are invalid, or do not match the number of elements in the bytes.
+    #[error("invalid dimensions: {0}")]
+    Dimensions(#[from] DimensionsError),
+}
+
+impl<T: Pod> Tensor<T, CpuBackend> {
+    /// Returns the raw bytes of the tensor's elements, in row-major order.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        bytemuck::cast_slice(self.as_slice())
+    }
+
+    /// Copies a byte slice into a tensor of the given sizes.
+    ///
+    /// Unlike [TensorView::from_bytes], the byte slice does not need to be aligned for `T`, but it
+    /// must contain exactly the number of elements implied by `sizes`.
+    pub fn from_bytes(bytes: &[u8], sizes: impl AsRef<[usize]>) -> Result<Self, TensorBytesError> {
+        let dimensions = Dimensions::try_from(sizes.as_ref())?;
+        if bytes.len() % size_of::<T>() != 0 {
+            return Err(TensorBytesError::Cast(PodCastError::OutputSliceWouldHaveSlop));
+        }
+        let num_elements = bytes.len() / size_of::<T>();
+        if dimensions.total_len() != num_elements {
+            return Err(
+                DimensionsError::NumElementsMismatch(dimensions.total_len(), num_elements).into()
+            );
+        }
+        let values = bytes
+            .chunks_exact(size_of::<T>())
+            .map(bytemuck::pod_read_unaligned)
+            .collect::<Vec<T>>();
+        Ok(Tensor::from(values).reshape(dimensions.sizes()))
+    }
+}
+
+impl<'a, T: Pod> TensorView<'a, T, CpuBackend> {
+    /// Views a byte slice as a tensor of the given sizes, without copying.
+    ///
+    /// The byte slice must be aligned for `T` and contain exactly the number of elements implied by
+    /// `sizes`.
+    pub fn from_bytes(
+        bytes: &'a [u8],
+        sizes: impl AsRef<[usize]>,
+    ) -> Result<Self, TensorBytesError> {
+        let values: &[T] = bytemuck::try_cast_slice(bytes).map_err(TensorBytesError::Cast)?;
+        Ok(Self::from_slice(values, sizes)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::{AbstractField, PrimeField32};
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+
+    #[test]
+    fn test_bytes_round_trip_baby_bear() {
+        // `BabyBear` is not `Pod`, so its elements go through their canonical `u32` values.
+        let mut rng = thread_rng();
+        let elements = (0..24).map(|_| rng.gen::<BabyBear>()).collect::<Vec<_>>();
+        let tensor = elements
+            .iter()
+            .map(PrimeField32::as_canonical_u32)
+            .collect::<Tensor<u32>>()
+            .reshape([4, 6]);
+        let bytes = tensor.as_bytes();
+
+        let view = TensorView::<u32>::from_bytes(bytes, [4, 6]).unwrap();
+        assert_eq!(view.sizes(), [4, 6]);
+        let copy = Tensor::<u32>::from_bytes(bytes, [4, 6]).unwrap();
+        assert_eq!(copy.sizes(), [4, 6]);
+
+        for values in [view.as_slice(), copy.as_slice()] {
+            let decoded =
+                values.iter().map(|&x| BabyBear::from_canonical_u32(x)).collect::<Vec<_>>();
+            assert_eq!(decoded, elements);
+        }
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_wrong_length() {
+        let tensor = (0..12u32).collect::<Tensor<u32>>();
+        let bytes = tensor.as_bytes();
+        assert!(matches!(
+            Tensor::<u32>::from_bytes(bytes, [4, 4]),
+            Err(TensorBytesError::Dimensions(DimensionsError::NumElementsMismatch(16, 12)))
+        ));
+        assert!(matches!(
+            Tensor::<u32>::from_bytes(&bytes[..bytes.len() - 1], [11]),
+            Err(TensorBytesError::Cast(_))
+        ));
+        assert!(TensorView::<u32>::from_bytes(bytes, [3, 5]).is_err());
+    }
+}
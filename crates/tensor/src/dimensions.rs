@@ -20,6 +20,15 @@ pub enum DimensionsError {
     NumElementsMismatch(usize, usize),
 }
 
+/// How the elements of a tensor fit into a target shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaddingPlan {
+    /// The target has the same number of elements.
+    Exact,
+    /// The target is larger by the given number of elements.
+    Pad(usize),
+}
+
 impl Dimensions {
     fn new(sizes: ArrayVec<usize, MAX_DIMENSIONS>) -> Self {
         let mut strides = ArrayVec::new();
@@ -32,6 +41,21 @@ impl Dimensions {
         Self { sizes, strides }
     }
 
+    /// The dimensions of a matrix with `rows` rows and `cols` columns.
+    #[inline]
+    pub fn matrix(rows: usize, cols: usize) -> Self {
+        Self::new(ArrayVec::from_iter([rows, cols]))
+    }
+
+    /// Returns `(rows, cols)` if the dimensions are two dimensional.
+    #[inline]
+    pub fn as_matrix_dims(&self) -> Option<(usize, usize)> {
+        match self.sizes.as_slice() {
+            [rows, cols] => Some((*rows, *cols)),
+            _ => None,
+        }
+    }
+
     #[inline]
     pub fn total_len(&self) -> usize {
         self.sizes.iter().product()
@@ -45,6 +69,20 @@ impl Dimensions {
         Ok(())
     }
 
+    /// Checks whether `self` can be reshaped into `target`, possibly after padding the elements at
+    /// the end.
+    #[inline]
+    pub fn compatible_or_padded(
+        &self,
+        target: &Dimensions,
+    ) -> Result<PaddingPlan, DimensionsError> {
+        match target.total_len().checked_sub(self.total_len()) {
+            Some(0) => Ok(PaddingPlan::Exact),
+            Some(pad) => Ok(PaddingPlan::Pad(pad)),
+            None => Err(DimensionsError::NumElementsMismatch(self.total_len(), target.total_len())),
+        }
+    }
+
     #[inline]
     pub fn sizes(&self) -> &[usize] {
         &self.sizes
@@ -119,3 +157,37 @@ impl<'de> Deserialize<'de> for Dimensions {
         Ok(Self::try_from(sizes).expect("invalid dimension length"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dimensions(sizes: &[usize]) -> Dimensions {
+        sizes.try_into().unwrap()
+    }
+
+    #[test]
+    fn test_compatible_or_padded() {
+        let source = dimensions(&[4, 3]);
+        assert!(matches!(
+            source.compatible_or_padded(&dimensions(&[2, 6])),
+            Ok(PaddingPlan::Exact)
+        ));
+        assert!(matches!(source.compatible_or_padded(&dimensions(&[16])), Ok(PaddingPlan::Pad(4))));
+        assert!(matches!(
+            source.compatible_or_padded(&dimensions(&[2, 5])),
+            Err(DimensionsError::NumElementsMismatch(12, 10))
+        ));
+    }
+
+    #[test]
+    fn test_matrix() {
+        let matrix = Dimensions::matrix(4, 3);
+        assert_eq!(matrix, dimensions(&[4, 3]));
+        assert_eq!(matrix.strides(), [3, 1]);
+        assert_eq!(matrix.as_matrix_dims(), Some((4, 3)));
+
+        assert_eq!(dimensions(&[12]).as_matrix_dims(), None);
+        assert_eq!(dimensions(&[2, 2, 3]).as_matrix_dims(), None);
+    }
+}
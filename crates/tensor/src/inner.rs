@@ -1,7 +1,7 @@
 use std::{
     marker::PhantomData,
     mem::ManuallyDrop,
-    ops::{Index, IndexMut},
+    ops::{Index, IndexMut, Mul},
 };
 
 use derive_where::derive_where;
@@ -10,7 +10,7 @@ use p3_matrix::Matrix;
 use rand::{distributions::Standard, prelude::Distribution, Rng};
 use serde::{ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::{Dimensions, DimensionsError};
+use crate::{Dimensions, DimensionsError, PaddingPlan};
 
 #[derive(Debug, Clone)]
 #[derive_where(PartialEq, Eq; Buffer<T, A>)]
@@ -91,6 +91,22 @@ impl<T, A: Backend> Tensor<T, A> {
         self
     }
 
+    /// Inserts a dimension of size one at position `axis`, e.g. turning sizes `[n]` into `[1, n]`
+    /// for `axis == 0`.
+    #[inline]
+    #[track_caller]
+    pub fn unsqueeze(self, axis: usize) -> Self {
+        let mut sizes = self.sizes().to_vec();
+        assert!(
+            axis <= sizes.len(),
+            "Tensor::unsqueeze: axis {} out of range for {} dimensions",
+            axis,
+            sizes.len()
+        );
+        sizes.insert(axis, 1);
+        self.reshape(sizes)
+    }
+
     #[inline]
     pub fn into_buffer(self) -> Buffer<T, A> {
         self.storage
@@ -156,6 +172,13 @@ impl<T, A: Backend> Tensor<T, A> {
         self.dimensions.total_len()
     }
 
+    /// Returns the number of bytes allocated for the tensor's storage, counting its full capacity
+    /// rather than only the elements in use.
+    #[inline]
+    pub fn memory_bytes(&self) -> usize {
+        self.storage.capacity() * std::mem::size_of::<T>()
+    }
+
     pub fn as_mut_ptr(&mut self) -> *mut T {
         self.storage.as_mut_ptr()
     }
@@ -194,6 +217,21 @@ impl<T, A: Backend> Tensor<T, A> {
         self.as_view_mut().split_mut()
     }
 
+    /// Returns an iterator over every coordinate of the tensor, in row-major order.
+    pub fn iter_indices(&self) -> impl Iterator<Item = Vec<usize>> {
+        let sizes = self.sizes().to_vec();
+        let total_len = self.total_len();
+        (0..total_len).map(move |flat| {
+            let mut remainder = flat;
+            let mut index = vec![0; sizes.len()];
+            for (i, size) in index.iter_mut().zip(sizes.iter()).rev() {
+                *i = remainder % size;
+                remainder /= size;
+            }
+            index
+        })
+    }
+
     /// # Safety
     ///
     /// See [std::mem::MaybeUninit::assume_init].
@@ -241,6 +279,28 @@ impl<T, A: Backend> From<Buffer<T, A>> for Tensor<T, A> {
     }
 }
 
+/// Conversion of a buffer into a tensor of given dimensions.
+pub trait IntoTensor<T, A: Backend> {
+    /// Turns the buffer into a tensor with dimensions `sizes`, panicking if their total length
+    /// differs from the length of the buffer.
+    fn into_tensor(self, sizes: impl AsRef<[usize]>) -> Tensor<T, A>;
+}
+
+impl<T, A: Backend> IntoTensor<T, A> for Buffer<T, A> {
+    #[inline]
+    #[track_caller]
+    fn into_tensor(self, sizes: impl AsRef<[usize]>) -> Tensor<T, A> {
+        let dimensions: Dimensions = sizes.as_ref().try_into().unwrap();
+        assert_eq!(
+            dimensions.total_len(),
+            self.len(),
+            "Buffer::into_tensor: dimensions {:?} do not match buffer length",
+            dimensions
+        );
+        Tensor { storage: self, dimensions }
+    }
+}
+
 impl<T> From<Vec<T>> for Tensor<T, CpuBackend> {
     #[inline]
     fn from(vec: Vec<T>) -> Self {
@@ -257,7 +317,7 @@ impl<T> FromIterator<T> for Tensor<T, CpuBackend> {
 
 impl<T: Clone + Send + Sync> From<p3_matrix::dense::RowMajorMatrix<T>> for Tensor<T, CpuBackend> {
     fn from(value: p3_matrix::dense::RowMajorMatrix<T>) -> Self {
-        let dimensions: Dimensions = [value.height(), value.width()].try_into().unwrap();
+        let dimensions = Dimensions::matrix(value.height(), value.width());
         let storage = Buffer::from(value.values);
         Self { storage, dimensions }
     }
@@ -268,10 +328,9 @@ impl<T: Clone + Send + Sync> TryFrom<Tensor<T, CpuBackend>>
 {
     type Error = DimensionsError;
     fn try_from(value: Tensor<T, CpuBackend>) -> Result<Self, Self::Error> {
-        if value.sizes().len() != 2 {
+        let Some((_, width)) = value.shape().as_matrix_dims() else {
             return Err(DimensionsError::TooManyDimensions(value.sizes().len()));
-        }
-        let width = value.sizes()[1];
+        };
         let values = value.storage.into_vec();
         Ok(Self::new(values, width))
     }
@@ -292,6 +351,27 @@ impl<T> Tensor<T, CpuBackend> {
         Tensor::with_sizes_in(sizes, GLOBAL_CPU_BACKEND)
     }
 
+    /// Creates a tensor of the given sizes whose entries are `f(index)`, filled in row-major
+    /// order.
+    #[track_caller]
+    pub fn from_fn(sizes: impl AsRef<[usize]>, mut f: impl FnMut(&[usize]) -> T) -> Self {
+        let dimensions: Dimensions = sizes.as_ref().try_into().unwrap();
+        let total_len = dimensions.total_len();
+        let mut index = vec![0; dimensions.sizes().len()];
+        let mut values = Vec::with_capacity(total_len);
+        for _ in 0..total_len {
+            values.push(f(&index));
+            for (i, size) in index.iter_mut().zip(dimensions.sizes()).rev() {
+                *i += 1;
+                if *i < *size {
+                    break;
+                }
+                *i = 0;
+            }
+        }
+        Self { storage: Buffer::from(values), dimensions }
+    }
+
     #[inline]
     pub fn as_slice(&self) -> &[T] {
         &self.storage[..]
@@ -301,6 +381,165 @@ impl<T> Tensor<T, CpuBackend> {
     pub fn as_mut_slice(&mut self) -> &mut [T] {
         &mut self.storage[..]
     }
+
+    /// Reshapes the tensor into `sizes`, appending copies of `pad_value` if the new shape has more
+    /// elements.
+    #[track_caller]
+    pub fn reshape_padded(mut self, sizes: impl AsRef<[usize]>, pad_value: T) -> Self
+    where
+        T: Clone,
+    {
+        let dimensions: Dimensions = sizes.as_ref().try_into().unwrap();
+        match self.dimensions.compatible_or_padded(&dimensions) {
+            Ok(PaddingPlan::Exact) => {}
+            Ok(PaddingPlan::Pad(_)) => self.storage.resize(dimensions.total_len(), pad_value),
+            Err(e) => panic!("Tensor::reshape_padded: {}", e),
+        }
+        self.dimensions = dimensions;
+        self
+    }
+
+    /// Returns a tensor of the same shape with every element multiplied by `scalar`.
+    ///
+    /// The multiplication is done in parallel with the `rayon` feature.
+    pub fn scale(&self, scalar: T) -> Self
+    where
+        T: Copy + Mul<Output = T> + Send + Sync,
+    {
+        #[cfg(feature = "rayon")]
+        let values = {
+            use rayon::prelude::*;
+            self.as_slice().par_iter().map(|&x| x * scalar).collect::<Vec<_>>()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let values = self.as_slice().iter().map(|&x| x * scalar).collect::<Vec<_>>();
+        Self { storage: Buffer::from(values), dimensions: self.dimensions.clone() }
+    }
+
+    /// Panics if the tensor contains a NaN or infinite value, reporting the first such index.
+    ///
+    /// This is a debugging aid for tensors of floats, e.g. in benchmark harnesses.
+    #[cfg(feature = "float-debug")]
+    #[track_caller]
+    pub fn assert_finite(&self)
+    where
+        T: num_traits::Float + std::fmt::Debug,
+    {
+        if let Some((i, value)) = self.as_slice().iter().enumerate().find(|(_, x)| !x.is_finite()) {
+            panic!("tensor element {i} is not finite: {value:?}");
+        }
+    }
+
+    /// Compares the flattened contents of two tensors, ignoring their shapes.
+    ///
+    /// Unlike the strict [PartialEq] implementation, which also compares the dimensions, this
+    /// considers e.g. a `[n]` tensor and a `[n, 1]` tensor holding the same elements to be equal.
+    #[inline]
+    pub fn data_eq(&self, other: &Self) -> bool
+    where
+        T: PartialEq,
+    {
+        self.total_len() == other.total_len() && self.as_slice() == other.as_slice()
+    }
+
+    /// Returns the flat indices at which the elements of two tensors of the same shape differ.
+    ///
+    /// This is a diagnostic complement to [PartialEq], e.g. for locating mismatching evaluations.
+    #[track_caller]
+    pub fn diff_indices(&self, other: &Self) -> Vec<usize>
+    where
+        T: PartialEq,
+    {
+        assert_eq!(self.sizes(), other.sizes(), "tensor shapes must match");
+        self.as_slice()
+            .iter()
+            .zip(other.as_slice().iter())
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns a copy of the tensor with its axes reordered, so that axis `i` of the result is
+    /// axis `axes[i]` of `self`.
+    ///
+    /// The elements are physically moved, so the result is laid out in row-major order for its
+    /// new sizes.
+    #[track_caller]
+    pub fn permute_axes(&self, axes: impl AsRef<[usize]>) -> Self
+    where
+        T: Clone,
+    {
+        let axes = axes.as_ref();
+        let num_dimensions = self.sizes().len();
+        assert!(
+            axes.len() == num_dimensions && (0..num_dimensions).all(|axis| axes.contains(&axis)),
+            "invalid axis permutation {:?} for a tensor with {} dimensions",
+            axes,
+            num_dimensions
+        );
+
+        let dimensions: Dimensions = axes.iter().map(|&axis| self.sizes()[axis]).collect();
+        let strides = axes.iter().map(|&axis| self.strides()[axis]).collect::<Vec<_>>();
+        let total_len = dimensions.total_len();
+        let mut values = Vec::with_capacity(total_len);
+        let mut index = vec![0; num_dimensions];
+        for _ in 0..total_len {
+            let offset = index.iter().zip(strides.iter()).map(|(i, s)| i * s).sum::<usize>();
+            values.push(self.as_slice()[offset].clone());
+            // Advance the index in row-major order of the new dimensions.
+            for (i, size) in index.iter_mut().zip(dimensions.sizes()).rev() {
+                *i += 1;
+                if *i < *size {
+                    break;
+                }
+                *i = 0;
+            }
+        }
+        Self { storage: Buffer::from(values), dimensions }
+    }
+
+    /// Splits the tensor along its leading dimension into rows `[0..mid]` and `[mid..]`.
+    ///
+    /// Both halves keep the remaining dimensions of `self`.
+    #[track_caller]
+    pub fn split_axis0_at(self, mid: usize) -> (Self, Self)
+    where
+        T: Clone,
+    {
+        let len = self.sizes()[0];
+        assert!(mid <= len, "split index {} out of range for leading dimension {}", mid, len);
+        let row_len = self.strides()[0];
+        let (first, second) = self.as_slice().split_at(mid * row_len);
+
+        let mut first_sizes = self.sizes().to_vec();
+        first_sizes[0] = mid;
+        let mut second_sizes = self.sizes().to_vec();
+        second_sizes[0] = len - mid;
+
+        (
+            Self::from(first.to_vec()).reshape(first_sizes),
+            Self::from(second.to_vec()).reshape(second_sizes),
+        )
+    }
+
+    /// Returns a tensor made of the slices `self[[row, ..]]` for each `row` in `rows`, in order.
+    pub fn select_axis0(&self, rows: &[usize]) -> Self
+    where
+        T: Clone,
+    {
+        let len = self.sizes()[0];
+        let row_len = self.strides()[0];
+        let mut values = Vec::with_capacity(rows.len() * row_len);
+        for &row in rows {
+            assert!(row < len, "row {} out of range for leading dimension {}", row, len);
+            values.extend_from_slice(&self.as_slice()[row * row_len..(row + 1) * row_len]);
+        }
+
+        let mut sizes = self.sizes().to_vec();
+        sizes[0] = rows.len();
+        Self::from(values).reshape(sizes)
+    }
 }
 
 #[derive(Debug)]
@@ -485,6 +724,18 @@ impl<'a, T, A: Backend> TensorViewMut<'a, T, A> {
 }
 
 impl<'a, T> TensorView<'a, T, CpuBackend> {
+    /// Views a slice as a tensor of the given sizes, without copying.
+    pub fn from_slice(
+        values: &'a [T],
+        sizes: impl AsRef<[usize]>,
+    ) -> Result<Self, DimensionsError> {
+        let dimensions = Dimensions::try_from(sizes.as_ref())?;
+        if dimensions.total_len() != values.len() {
+            return Err(DimensionsError::NumElementsMismatch(dimensions.total_len(), values.len()));
+        }
+        Ok(Self { ptr: values.as_ptr(), dimensions, _marker: PhantomData })
+    }
+
     #[inline]
     pub fn as_slice(self) -> &'a [T] {
         unsafe { std::slice::from_raw_parts(self.ptr, self.dimensions.total_len()) }
@@ -666,3 +917,197 @@ impl<'de, T: Deserialize<'de>> Deserialize<'de> for Tensor<T> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_eq_ignores_shape() {
+        let flat = Tensor::from(vec![1u32, 2, 3, 4]);
+        let column = Tensor::from(vec![1u32, 2, 3, 4]).reshape([4, 1]);
+        let row = Tensor::from(vec![1u32, 2, 3, 4]).reshape([1, 4]);
+
+        // The strict equality also compares the dimensions.
+        assert_ne!(flat, column);
+        assert_ne!(flat, row);
+
+        for (a, b) in [(&flat, &column), (&flat, &row), (&column, &row)] {
+            assert!(a.data_eq(b));
+            assert!(b.data_eq(a));
+        }
+    }
+
+    #[test]
+    fn test_diff_indices() {
+        let a = (0..12u32).collect::<Tensor<u32>>().reshape([3, 4]);
+        let mut b = a.clone();
+        assert!(a.diff_indices(&b).is_empty());
+        b.as_mut_slice()[7] = 100;
+        assert_eq!(a.diff_indices(&b), vec![7]);
+    }
+
+    #[test]
+    #[should_panic(expected = "tensor shapes must match")]
+    fn test_diff_indices_shape_mismatch() {
+        let a = (0..12u32).collect::<Tensor<u32>>().reshape([3, 4]);
+        a.diff_indices(&a.clone().reshape([4, 3]));
+    }
+
+    #[test]
+    fn test_iter_indices() {
+        let tensor = (0..24u32).collect::<Tensor<u32>>().reshape([2, 3, 4]);
+        let indices = tensor.iter_indices().collect::<Vec<_>>();
+        assert_eq!(indices.len(), tensor.total_len());
+        assert_eq!(indices[0], vec![0, 0, 0]);
+        assert_eq!(indices[1], vec![0, 0, 1]);
+        assert_eq!(indices[4], vec![0, 1, 0]);
+        assert_eq!(indices[23], vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_reshape_padded() {
+        let tensor = (0..6u32).collect::<Tensor<u32>>();
+        let exact = tensor.clone().reshape_padded([2, 3], 0);
+        assert_eq!(exact, tensor.clone().reshape([2, 3]));
+        let padded = tensor.reshape_padded([2, 4], 7);
+        assert_eq!(padded.sizes(), [2, 4]);
+        assert_eq!(padded.as_slice(), [0, 1, 2, 3, 4, 5, 7, 7]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_reshape_padded_rejects_smaller_target() {
+        (0..6u32).collect::<Tensor<u32>>().reshape_padded([5], 0);
+    }
+
+    #[test]
+    fn test_from_fn() {
+        let tensor = Tensor::from_fn([3, 4], |index| index[0] * 10 + index[1]);
+        assert_eq!(tensor.sizes(), [3, 4]);
+        assert_eq!(tensor.as_slice(), [0, 1, 2, 3, 10, 11, 12, 13, 20, 21, 22, 23]);
+    }
+
+    #[test]
+    fn test_permute_axes() {
+        let tensor = (0..24u32).collect::<Tensor<u32>>().reshape([2, 3, 4]);
+        let permuted = tensor.permute_axes([2, 0, 1]);
+        assert_eq!(permuted.sizes(), [4, 2, 3]);
+        for i in 0..2 {
+            for j in 0..3 {
+                for k in 0..4 {
+                    let value = (i * 12 + j * 4 + k) as u32;
+                    assert_eq!(permuted.as_slice()[k * 6 + i * 3 + j], value);
+                }
+            }
+        }
+        assert_eq!(tensor.permute_axes([0, 1, 2]), tensor);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_permute_axes_rejects_invalid_permutation() {
+        let tensor = (0..6u32).collect::<Tensor<u32>>().reshape([2, 3]);
+        tensor.permute_axes([0, 0]);
+    }
+
+    #[test]
+    fn test_split_axis0_at() {
+        let tensor = (0..12u32).collect::<Tensor<u32>>().reshape([4, 3]);
+        for mid in 0..=4 {
+            let (first, second) = tensor.clone().split_axis0_at(mid);
+            assert_eq!(first.sizes(), [mid, 3]);
+            assert_eq!(second.sizes(), [4 - mid, 3]);
+            assert_eq!(first.as_slice(), &tensor.as_slice()[..mid * 3]);
+            assert_eq!(second.as_slice(), &tensor.as_slice()[mid * 3..]);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_split_axis0_at_out_of_range() {
+        let tensor = (0..12u32).collect::<Tensor<u32>>().reshape([4, 3]);
+        tensor.split_axis0_at(5);
+    }
+
+    #[test]
+    fn test_data_eq_compares_contents() {
+        let a = Tensor::from(vec![1u32, 2, 3, 4]);
+        assert!(!a.data_eq(&Tensor::from(vec![1u32, 2, 3, 5]).reshape([4, 1])));
+        assert!(!a.data_eq(&Tensor::from(vec![1u32, 2, 3])));
+        assert!(!a.data_eq(&Tensor::from(vec![1u32, 2, 3, 4, 0]).reshape([5, 1])));
+    }
+
+    #[test]
+    fn test_scale() {
+        let tensor = (0..12u32).collect::<Tensor<u32>>().reshape([2, 3, 2]);
+        let scaled = tensor.scale(5);
+        assert_eq!(scaled.sizes(), [2, 3, 2]);
+        assert_eq!(scaled.as_slice(), (0..12u32).map(|x| 5 * x).collect::<Vec<_>>());
+        assert_eq!(tensor.scale(1), tensor);
+        assert!(tensor.scale(0).as_slice().iter().all(|&x| x == 0));
+    }
+
+    #[cfg(feature = "float-debug")]
+    #[test]
+    fn test_assert_finite() {
+        Tensor::from(vec![0.0f64, -1.5, 2.0]).assert_finite();
+    }
+
+    #[cfg(feature = "float-debug")]
+    #[test]
+    #[should_panic(expected = "tensor element 2 is not finite: NaN")]
+    fn test_assert_finite_nan() {
+        Tensor::from(vec![0.0f64, 1.0, f64::NAN, f64::INFINITY]).reshape([2, 2]).assert_finite();
+    }
+
+    #[cfg(feature = "float-debug")]
+    #[test]
+    #[should_panic(expected = "tensor element 1 is not finite: inf")]
+    fn test_assert_finite_infinite() {
+        Tensor::from(vec![1.0f32, f32::INFINITY]).assert_finite();
+    }
+
+    #[test]
+    fn test_unsqueeze() {
+        let tensor = (0..6u32).collect::<Tensor<u32>>().reshape([2, 3]);
+        for (axis, sizes) in [(0, [1, 2, 3]), (1, [2, 1, 3]), (2, [2, 3, 1])] {
+            let unsqueezed = tensor.clone().unsqueeze(axis);
+            assert_eq!(unsqueezed.sizes(), sizes);
+            assert_eq!(unsqueezed.as_slice(), tensor.as_slice());
+        }
+        assert_eq!(Tensor::from(vec![1u32, 2, 3]).unsqueeze(0).sizes(), [1, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "axis 3 out of range for 2 dimensions")]
+    fn test_unsqueeze_out_of_range() {
+        (0..6u32).collect::<Tensor<u32>>().reshape([2, 3]).unsqueeze(3);
+    }
+
+    #[test]
+    fn test_into_tensor() {
+        let tensor = Buffer::from((0..12u32).collect::<Vec<_>>()).into_tensor([3, 4]);
+        assert_eq!(tensor, (0..12u32).collect::<Tensor<u32>>().reshape([3, 4]));
+        assert_eq!(tensor.sizes(), [3, 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "do not match buffer length")]
+    fn test_into_tensor_length_mismatch() {
+        Buffer::from((0..12u32).collect::<Vec<_>>()).into_tensor([5, 2]);
+    }
+
+    #[test]
+    fn test_memory_bytes() {
+        let mut values = Vec::<u64>::with_capacity(16);
+        values.extend(0..12);
+        let capacity = values.capacity();
+        let tensor = Tensor::from(values).reshape([3, 4]);
+        // The whole allocation is counted, not only the elements in use.
+        assert_eq!(tensor.memory_bytes(), capacity * 8);
+        assert!(tensor.memory_bytes() >= 16 * 8);
+
+        assert_eq!(Tensor::from(vec![0u32; 5]).memory_bytes(), 5 * 4);
+    }
+}
@@ -1,5 +1,9 @@
+#[cfg(feature = "bytemuck")]
+mod bytes;
 mod dimensions;
 mod inner;
 
+#[cfg(feature = "bytemuck")]
+pub use bytes::*;
 pub use dimensions::*;
 pub use inner::*;
@@ -21,6 +21,24 @@ pub const fn indices_arr<const N: usize>() -> [usize; N] {
 #[track_caller]
 #[inline]
 pub fn next_multiple_of_32(n: usize, fixed_height: Option<usize>) -> usize {
+    next_multiple_of_pow2(n, 32, 16, fixed_height)
+}
+
+/// Returns the internal value of the option if it is set, otherwise returns the next multiple of
+/// `align`, and at least `floor`.
+///
+/// # Panics
+///
+/// Panics if `align` is not a power of two, or if `fixed_height` is smaller than `n`.
+#[track_caller]
+#[inline]
+pub fn next_multiple_of_pow2(
+    n: usize,
+    align: usize,
+    floor: usize,
+    fixed_height: Option<usize>,
+) -> usize {
+    assert!(align.is_power_of_two(), "alignment must be a power of two: got {}", align);
     match fixed_height {
         Some(height) => {
             if n > height {
@@ -28,12 +46,42 @@ pub fn next_multiple_of_32(n: usize, fixed_height: Option<usize>) -> usize {
             }
             height
         }
-        None => {
-            let mut padded_nb_rows = n.next_multiple_of(32);
-            if padded_nb_rows < 16 {
-                padded_nb_rows = 16;
-            }
-            padded_nb_rows
-        }
+        None => n.next_multiple_of(align).max(floor),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_multiple_of_32() {
+        assert_eq!(next_multiple_of_32(0, None), 16);
+        assert_eq!(next_multiple_of_32(1, None), 32);
+        assert_eq!(next_multiple_of_32(32, None), 32);
+        assert_eq!(next_multiple_of_32(33, None), 64);
+        assert_eq!(next_multiple_of_32(33, Some(40)), 40);
+    }
+
+    #[test]
+    fn test_next_multiple_of_pow2() {
+        assert_eq!(next_multiple_of_pow2(0, 8, 4, None), 4);
+        assert_eq!(next_multiple_of_pow2(3, 8, 4, None), 8);
+        assert_eq!(next_multiple_of_pow2(9, 8, 4, None), 16);
+        assert_eq!(next_multiple_of_pow2(9, 8, 64, None), 64);
+        assert_eq!(next_multiple_of_pow2(9, 1, 0, None), 9);
+        assert_eq!(next_multiple_of_pow2(9, 8, 64, Some(9)), 9);
+    }
+
+    #[test]
+    #[should_panic(expected = "fixed height is too small")]
+    fn test_fixed_height_too_small() {
+        next_multiple_of_32(33, Some(32));
+    }
+
+    #[test]
+    #[should_panic(expected = "alignment must be a power of two")]
+    fn test_alignment_not_power_of_two() {
+        next_multiple_of_pow2(5, 24, 0, None);
     }
 }